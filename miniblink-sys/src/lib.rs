@@ -1,4 +1,273 @@
 #![allow(unused, non_snake_case, non_camel_case_types, non_upper_case_globals)]
+#![allow(
+    clippy::missing_safety_doc,
+    clippy::too_many_arguments,
+    clippy::type_complexity
+)]
 mod miniblink;
 
 pub use miniblink::*;
+
+macro_rules! impl_has {
+    ($($name:ident),* $(,)?) => {
+        impl Library {
+            /// Check if the loaded library exports the function `symbol`.
+            ///
+            /// Older versions of `mb.dll` lack some of the newer exports.
+            pub fn has(&self, symbol: &str) -> bool {
+                match symbol {
+                    $(stringify!($name) => self.$name.is_ok(),)*
+                    _ => false,
+                }
+            }
+
+            /// The names of the functions `has` knows about.
+            pub const EXPORTS: &'static [&'static str] = &[$(stringify!($name)),*];
+        }
+    };
+}
+
+// Every function of `Library`. Regenerate from the fields in `miniblink.rs` after running `bind.bat`.
+impl_has! {
+    mbInit,
+    mbUninit,
+    mbCreateInitSettings,
+    mbSetInitSettings,
+    mbCreateWebView,
+    mbCreateWebViewBindGtkWindow,
+    mbDestroyWebView,
+    mbCreateWebWindow,
+    mbCreateWebWindowEx,
+    mbCreateWebCustomWindow,
+    mbMoveWindow,
+    mbMoveToCenter,
+    mbSetAutoDrawToHwnd,
+    mbGetCaretRect,
+    mbSetAudioMuted,
+    mbIsAudioMuted,
+    mbCreateString,
+    mbCreateStringWithCopy,
+    mbCreateStringWithoutNullTermination,
+    mbDeleteString,
+    mbGetStringLen,
+    mbGetString,
+    mbSetProxy,
+    mbSetDebugConfig,
+    mbNetSetData,
+    mbNetHookRequest,
+    mbNetChangeRequestUrl,
+    mbNetContinueJob,
+    mbNetGetRawHttpHeadInBlinkThread,
+    mbNetGetRawResponseHeadInBlinkThread,
+    mbNetHoldJobToAsynCommit,
+    mbNetCancelRequest,
+    mbNetOnResponse,
+    mbNetSetWebsocketCallback,
+    mbNetSendWsText,
+    mbNetSendWsBlob,
+    mbNetEnableResPacket,
+    mbNetGetPostBody,
+    mbNetCreatePostBodyElements,
+    mbNetFreePostBodyElements,
+    mbNetCreatePostBodyElement,
+    mbNetFreePostBodyElement,
+    mbNetCreateWebUrlRequest,
+    mbNetAddHTTPHeaderFieldToUrlRequest,
+    mbNetStartUrlRequest,
+    mbNetGetHttpStatusCode,
+    mbNetGetRequestMethod,
+    mbNetGetExpectedContentLength,
+    mbNetGetResponseUrl,
+    mbNetCancelWebUrlRequest,
+    mbSetViewProxy,
+    mbNetSetMIMEType,
+    mbNetGetMIMEType,
+    mbNetGetHTTPHeaderField,
+    mbNetSetHTTPHeaderField,
+    mbNetSetHTTPHeaderFieldUtf8,
+    mbSetMouseEnabled,
+    mbSetTouchEnabled,
+    mbSetSystemTouchEnabled,
+    mbSetContextMenuEnabled,
+    mbSetNavigationToNewWindowEnable,
+    mbSetHeadlessEnabled,
+    mbSetDragDropEnable,
+    mbSetDragEnable,
+    mbSetContextMenuItemShow,
+    mbSetHandle,
+    mbSetHandleOffset,
+    mbGetPlatformWindowHandle,
+    mbGetHostHWND,
+    mbSetTransparent,
+    mbSetViewSettings,
+    mbSetCspCheckEnable,
+    mbSetNpapiPluginsEnabled,
+    mbSetMemoryCacheEnable,
+    mbSetCookie,
+    mbSetCookieEnabled,
+    mbSetCookieJarPath,
+    mbSetCookieJarFullPath,
+    mbSetLocalStorageFullPath,
+    mbGetTitle,
+    mbSetWindowTitle,
+    mbSetWindowTitleW,
+    mbGetUrl,
+    mbGetCursorInfoType,
+    mbAddPluginDirectory,
+    mbSetUserAgent,
+    mbSetZoomFactor,
+    mbGetZoomFactor,
+    mbSetDiskCacheEnabled,
+    mbSetDiskCachePath,
+    mbSetDiskCacheLimit,
+    mbSetDiskCacheLimitDisk,
+    mbSetDiskCacheLevel,
+    mbSetResourceGc,
+    mbIsLoading,
+    mbCanGoBackOrForward,
+    mbCanGoBack,
+    mbCanGoForward,
+    mbGetCookie,
+    mbGetCookieOnBlinkThread,
+    mbClearCookie,
+    mbResize,
+    mbGetSize,
+    mbGetWindowRect,
+    mbOnNavigation,
+    mbOnNavigationSync,
+    mbOnCreateView,
+    mbOnDocumentReady,
+    mbOnPaintUpdated,
+    mbOnPaintBitUpdated,
+    mbOnAcceleratedPaint,
+    mbOnLoadUrlBegin,
+    mbOnLoadUrlEnd,
+    mbOnLoadUrlFail,
+    mbOnTitleChanged,
+    mbOnURLChanged,
+    mbOnLoadingFinish,
+    mbOnDownload,
+    mbOnDownloadInBlinkThread,
+    mbOnAlertBox,
+    mbOnConfirmBox,
+    mbOnPromptBox,
+    mbOnNetGetFavicon,
+    mbOnConsole,
+    mbOnClose,
+    mbOnDestroy,
+    mbOnPrinting,
+    mbOnDidCreateScriptContext,
+    mbOnWillReleaseScriptContext,
+    mbOnPluginList,
+    mbOnImageBufferToDataURL,
+    mbGoBack,
+    mbGoForward,
+    mbNavigateAtIndex,
+    mbGetNavigateIndex,
+    mbStopLoading,
+    mbReload,
+    mbPerformCookieCommand,
+    mbEditorSelectAll,
+    mbEditorCopy,
+    mbEditorCut,
+    mbEditorPaste,
+    mbEditorDelete,
+    mbEditorUndo,
+    mbFireMouseEvent,
+    mbFireContextMenuEvent,
+    mbFireMouseWheelEvent,
+    mbFireKeyUpEvent,
+    mbFireKeyDownEvent,
+    mbFireKeyPressEvent,
+    mbFireWindowsMessage,
+    mbSetFocus,
+    mbKillFocus,
+    mbShowWindow,
+    mbLoadURL,
+    mbLoadHtmlWithBaseUrl,
+    mbPostURL,
+    mbGetLockedViewDC,
+    mbUnlockViewDC,
+    mbWake,
+    mbJsToDouble,
+    mbJsToBoolean,
+    mbJsToString,
+    mbJsToWebFrameHandle,
+    mbGetParentWebFrameHandle,
+    mbGetJsValueType,
+    mbJsValueAddRef,
+    mbJsValueDeref,
+    mbOnJsQuery,
+    mbOnJsQueryEx,
+    mbResponseQuery,
+    mbRunJs,
+    mbRunJsSync,
+    mbWebFrameGetMainFrame,
+    mbIsMainFrame,
+    mbSetNodeJsEnable,
+    mbSetDeviceParameter,
+    mbGetContentAsMarkup,
+    mbGetSource,
+    mbGetWindowScreenshotSync,
+    mbGetSourceSync,
+    mbUtilSerializeToMHTML,
+    mbUtilCreateRequestCode,
+    mbUtilIsRegistered,
+    mbUtilPrint,
+    mbUtilBase64Encode,
+    mbUtilBase64EncodeBuffer,
+    mbUtilBase64Decode,
+    mbUtilDecodeURLEscape,
+    mbUtilEncodeURLEscape,
+    mbUtilCreateV8Snapshot,
+    mbUtilPrintToPdf,
+    mbUtilPrintToBitmap,
+    mbUtilScreenshot,
+    mbUtilsSilentPrint,
+    mbPopupDownloadMgr,
+    mbPopupDialogAndDownload,
+    mbDownloadByPath,
+    mbDownloadByUtf8Path,
+    mbGetPdfPageData,
+    mbCreateMemBuf,
+    mbFreeMemBuf,
+    mbPluginListBuilderAddPlugin,
+    mbPluginListBuilderAddMediaTypeToLastPlugin,
+    mbPluginListBuilderAddFileExtensionToLastMediaType,
+    mbEnableHighDPISupport,
+    mbRunMessageLoop,
+    mbExitMessageLoop,
+    mbOnLoadUrlFinish,
+    mbOnLoadUrlHeadersReceived,
+    mbOnDocumentReadyInBlinkThread,
+    mbUtilSetDefaultPrinterSettings,
+    mbGetContentWidth,
+    mbGetContentHeight,
+    mbGetWebViewForCurrentContext,
+    mbRegisterEmbedderCustomElement,
+    mbOnNodeCreateProcess,
+    mbGetGlobalExecByFrame,
+    mbJsToV8Value,
+    mbOnThreadIdle,
+    mbOnBlinkThreadInit,
+    mbCallBlinkThreadAsync,
+    mbCallBlinkThreadSync,
+    mbCallUiThreadSync,
+    mbCallUiThreadAsync,
+    mbSetUserKeyValue,
+    mbGetUserKeyValue,
+    mbGoToOffset,
+    mbGoToIndex,
+    mbEditorRedo,
+    mbEditorUnSelect,
+    mbGetBlinkMainThreadIsolate,
+    mbInsertCSSByFrame,
+    mbWebFrameGetMainWorldScriptContext,
+    mbNetGetReferrer,
+    mbPostToUiThread,
+    mbPostToUiThreadDelay,
+    mbSetEditable,
+    mbSetLanguage,
+    mbQueryState,
+    mbGetProcAddr,
+}
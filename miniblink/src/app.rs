@@ -1,24 +1,103 @@
 use std::{
     ffi::{c_void, OsStr},
     panic::AssertUnwindSafe,
+    sync::{atomic::Ordering, OnceLock},
+    thread::ThreadId,
 };
 
-use miniblink_sys::Library;
+use miniblink_sys::{mbSettings, Library};
 
 use crate::{
-    call_api_or_panic,
-    error::{MBError, MBResult},
-    LIB,
+    backend::Backend,
+    error::{MBError, MBResult, UnwrapOrGone},
+    mb_call,
+    types::{Capabilities, InitSettings, NativeInitSettings},
+    webview::WebView,
+    LIB, UNINITIALIZED,
 };
 
 /// Enable high dpi support.
 pub fn enable_high_dpi_support() {
-    unsafe { call_api_or_panic().mbEnableHighDPISupport() }
+    try_enable_high_dpi_support().unwrap_or_gone()
+}
+
+/// Fallible version of `enable_high_dpi_support`.
+pub fn try_enable_high_dpi_support() -> MBResult<()> {
+    unsafe { mb_call!(mbEnableHighDPISupport()) }
 }
 
 /// Run message loop provided by miniblink. Note: You may write your own message loop.
 pub fn run_message_loop() {
-    unsafe { call_api_or_panic().mbRunMessageLoop() }
+    try_run_message_loop().unwrap_or_gone()
+}
+
+/// Fallible version of `run_message_loop`.
+///
+/// # Remarks
+/// Returns `MBError::WrongThread` if not called on the thread which initialized miniblink.
+pub fn try_run_message_loop() -> MBResult<()> {
+    check_ui_thread()?;
+    unsafe { mb_call!(mbRunMessageLoop()) }
+}
+
+/// Exit the message loop. `run_message_loop` returns after the current message is handled.
+pub fn exit_message_loop() {
+    try_exit_message_loop().unwrap_or_gone()
+}
+
+/// Fallible version of `exit_message_loop`.
+pub fn try_exit_message_loop() -> MBResult<()> {
+    unsafe { mb_call!(mbExitMessageLoop()) }
+}
+
+/// Quit the application. Same as `exit_message_loop`.
+///
+/// # Remarks
+/// Call `uninit` after `run_message_loop` returns to release miniblink.
+pub fn quit() {
+    exit_message_loop()
+}
+
+/// Uninitialize miniblink.
+///
+/// Every live webview is destroyed, children before parents, and its callbacks are freed.
+/// Afterwards the fallible api returns `MBError::NotInitialized`, the non-fallible api does
+/// nothing and returns a default value, and miniblink can not be initialized again.
+///
+/// # Remarks
+/// Call it on the UI thread after `run_message_loop` returns. Does nothing if miniblink is not initialized.
+///
+/// ```rust,no_run
+/// use miniblink::{app, webview::WebView};
+///
+/// app::init("./mb.dll").unwrap();
+/// let view = WebView::default();
+/// view.load_url("https://miniblink.net/");
+/// app::run_message_loop();
+/// app::uninit();
+/// ```
+pub fn uninit() {
+    match try_uninit() {
+        Ok(()) | Err(MBError::NotInitialized) => (),
+        Err(error) => panic!("{error}"),
+    }
+}
+
+/// Fallible version of `uninit`.
+///
+/// # Remarks
+/// Returns `MBError::WrongThread` if not called on the thread which initialized miniblink. The
+/// webviews are still destroyed if the library does not export `mbUninit`.
+pub fn try_uninit() -> MBResult<()> {
+    check_ui_thread()?;
+    crate::call_backend()?;
+    WebView::destroy_all();
+    let result = match unsafe { mb_call!(mbUninit()) } {
+        Err(MBError::Unsupported(_)) => Ok(()),
+        result => result,
+    };
+    UNINITIALIZED.store(true, Ordering::SeqCst);
+    result
 }
 
 /// Initialize miniblink from `path`. Panic if failed to initialize.
@@ -26,21 +105,94 @@ pub fn init<P>(path: P) -> MBResult<&'static Library>
 where
     P: AsRef<OsStr>,
 {
-    if let Some(lib) = LIB.get() {
-        Ok(lib)
-    } else {
+    if LIB.get().is_none() {
         let lib =
             unsafe { Library::new(path) }.map_err(|e| MBError::LibraryUnloaded(e.to_string()))?;
-        let lib = LIB.get_or_init(|| lib);
-        unsafe {
-            let settings = std::mem::zeroed();
-            lib.mbInit(settings)
+        install(lib, None);
+    }
+    crate::call_api()
+}
+
+/// Initialize miniblink from `path` with `settings`.
+///
+/// # Remarks
+/// The settings are validated first, and kept alive as long as the library. Returns
+/// `MBError::AlreadyInitialized` if miniblink is already initialized, including by another
+/// thread racing this call, since the settings could not be applied.
+pub fn init_with_settings<P>(path: P, settings: InitSettings) -> MBResult<&'static Library>
+where
+    P: AsRef<OsStr>,
+{
+    settings.validate()?;
+    if LIB.get().is_some() {
+        return Err(MBError::AlreadyInitialized);
+    }
+    let lib = unsafe { Library::new(path) }.map_err(|e| MBError::LibraryUnloaded(e.to_string()))?;
+    match install(lib, Some(settings)) {
+        (_, true) => crate::call_api(),
+        (_, false) => Err(MBError::AlreadyInitialized),
+    }
+}
+
+/// Initialize miniblink with a custom backend, for example `backend::MockBackend` in tests.
+///
+/// # Remarks
+/// The backend is installed once per process. If miniblink is already initialized, the
+/// installed backend is returned and `backend` is dropped.
+pub fn init_with_backend<B>(backend: B) -> &'static dyn Backend
+where
+    B: Backend,
+{
+    install(backend, None).0
+}
+
+/// Query which `mb*` functions the loaded library exports.
+///
+/// # Remarks
+/// Older versions of `mb.dll` lack some exports. The functions wrapping them return
+/// `MBError::Unsupported` instead of calling into the library.
+pub fn capabilities() -> MBResult<Capabilities> {
+    let backend = crate::call_backend()?;
+    Ok(Capabilities { backend })
+}
+
+static UI_THREAD: OnceLock<ThreadId> = OnceLock::new();
+static INIT_SETTINGS: OnceLock<InitSettings> = OnceLock::new();
+static NATIVE_SETTINGS: OnceLock<NativeInitSettings> = OnceLock::new();
+
+/// Install the backend unless one is installed, and report whether it was.
+fn install<B>(backend: B, settings: Option<InitSettings>) -> (&'static dyn Backend, bool)
+where
+    B: Backend,
+{
+    let mut installed = false;
+    let installed_backend = LIB.get_or_init(|| {
+        installed = true;
+        UI_THREAD.get_or_init(|| std::thread::current().id());
+        let settings: *const mbSettings = match settings {
+            Some(settings) => {
+                let settings = INIT_SETTINGS.get_or_init(|| settings);
+                &NATIVE_SETTINGS
+                    .get_or_init(|| settings.to_native())
+                    .settings
+            }
+            None => std::ptr::null(),
         };
-        Ok(lib)
+        unsafe { backend.mbInit(settings) };
+        Box::new(backend)
+    });
+    (installed_backend.as_ref(), installed)
+}
+
+/// Check the current thread is the thread which initialized miniblink.
+pub(crate) fn check_ui_thread() -> MBResult<()> {
+    match UI_THREAD.get() {
+        Some(id) if *id != std::thread::current().id() => Err(MBError::WrongThread),
+        _ => Ok(()),
     }
 }
 
-pub(crate) fn invoke_command_sync<F, R>(handler: F) -> R
+pub(crate) fn invoke_command_sync<F, R>(handler: F) -> MBResult<R>
 where
     F: FnOnce() -> R + Send + 'static,
     R: Send,
@@ -53,7 +205,7 @@ where
     extern "system" fn callback<R>(param: *mut c_void, _: *mut c_void) {
         let param = unsafe { Box::from_raw(param as *mut Param<R>) };
         let handler = param.handler;
-        let result = std::panic::catch_unwind(AssertUnwindSafe(|| handler()));
+        let result = std::panic::catch_unwind(AssertUnwindSafe(handler));
         let result = result.unwrap();
         param
             .sender
@@ -61,19 +213,20 @@ where
             .expect("Failed to send result from UI thread");
     }
 
+    let api = crate::call_symbol("mbCallUiThreadSync")?;
     let (sender, receiver) = std::sync::mpsc::channel::<R>();
     let handler = Box::new(handler);
     let param = Box::into_raw(Box::new(Param { sender, handler }));
 
     unsafe {
-        call_api_or_panic().mbCallUiThreadSync(
+        api.mbCallUiThreadSync(
             Some(callback::<R>),
             param as *mut c_void,
             std::ptr::null_mut(),
         )
     };
 
-    receiver
+    Ok(receiver
         .recv()
-        .expect("Failed to receive result from UI thread")
+        .expect("Failed to receive result from UI thread"))
 }
@@ -0,0 +1,726 @@
+use std::any::Any;
+use std::collections::{HashMap, HashSet};
+use std::ffi::{c_char, c_int, c_void, CStr, CString};
+use std::sync::atomic::{AtomicIsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use miniblink_sys::*;
+use widestring::U16CStr;
+
+/// A value passed to or returned from `MockBackend`.
+#[derive(Debug, Clone)]
+pub enum MockValue {
+    /// Integers, booleans, enums and webview ids.
+    Int(i64),
+    /// Floating point numbers.
+    Float(f64),
+    /// Strings, both utf8 and wide.
+    Str(String),
+    /// Raw pointers, handles and null strings.
+    Ptr(usize),
+    /// Lists of strings, returned as a `mbSlist`.
+    List(Vec<String>),
+    /// Native callbacks. Use `MockValue::callback` to get the typed callback back.
+    Callback(Arc<dyn Any + Send + Sync>),
+}
+
+impl MockValue {
+    pub(crate) fn from_arg<T>(arg: T) -> Self
+    where
+        T: MockArg,
+    {
+        arg.into_mock()
+    }
+
+    /// Get the integer value.
+    pub fn as_int(&self) -> Option<i64> {
+        match self {
+            MockValue::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Get the floating point value.
+    pub fn as_float(&self) -> Option<f64> {
+        match self {
+            MockValue::Float(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Get the string value.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            MockValue::Str(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Get the pointer value.
+    pub fn as_ptr(&self) -> Option<usize> {
+        match self {
+            MockValue::Ptr(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Get the typed callback, such as `miniblink_sys::mbURLChangedCallback`.
+    pub fn callback<C>(&self) -> Option<C>
+    where
+        C: Copy + 'static,
+    {
+        match self {
+            MockValue::Callback(value) => value.downcast_ref::<C>().copied(),
+            _ => None,
+        }
+    }
+}
+
+pub(crate) trait MockArg {
+    fn into_mock(self) -> MockValue;
+}
+
+macro_rules! impl_mock_arg {
+    ($variant:ident as $as:ty: $($ty:ty),*) => {
+        $(
+            impl MockArg for $ty {
+                fn into_mock(self) -> MockValue {
+                    MockValue::$variant(self as $as)
+                }
+            }
+        )*
+    };
+}
+
+impl_mock_arg!(Int as i64: i32, u32, isize, i64, usize);
+impl_mock_arg!(Float as f64: f32, f64);
+impl_mock_arg!(Ptr as usize: *mut c_void, *mut mbRect, *const mbProxy, *const mbSettings, mbStringPtr, HWND);
+
+impl MockArg for *const c_char {
+    fn into_mock(self) -> MockValue {
+        if self.is_null() {
+            MockValue::Ptr(0)
+        } else {
+            let value = unsafe { CStr::from_ptr(self) };
+            MockValue::Str(value.to_string_lossy().to_string())
+        }
+    }
+}
+
+impl MockArg for *const u16 {
+    fn into_mock(self) -> MockValue {
+        if self.is_null() {
+            MockValue::Ptr(0)
+        } else {
+            let value = unsafe { U16CStr::from_ptr_str(self) };
+            MockValue::Str(value.to_string_lossy())
+        }
+    }
+}
+
+impl<F> MockArg for Option<F>
+where
+    F: Copy + Send + Sync + 'static,
+{
+    fn into_mock(self) -> MockValue {
+        MockValue::Callback(Arc::new(self))
+    }
+}
+
+pub(crate) trait MockReturn {
+    fn from_mock(value: Option<MockValue>) -> Self;
+}
+
+impl MockReturn for () {
+    fn from_mock(_: Option<MockValue>) -> Self {}
+}
+
+macro_rules! impl_mock_return {
+    (Int: $($ty:ty),*) => {
+        $(
+            impl MockReturn for $ty {
+                fn from_mock(value: Option<MockValue>) -> Self {
+                    match value {
+                        Some(MockValue::Int(value)) => value as _,
+                        _ => 0,
+                    }
+                }
+            }
+        )*
+    };
+    (Float: $($ty:ty),*) => {
+        $(
+            impl MockReturn for $ty {
+                fn from_mock(value: Option<MockValue>) -> Self {
+                    match value {
+                        Some(MockValue::Float(value)) => value as _,
+                        Some(MockValue::Int(value)) => value as _,
+                        _ => 0.0,
+                    }
+                }
+            }
+        )*
+    };
+    (Ptr: $($ty:ty),*) => {
+        $(
+            impl MockReturn for $ty {
+                fn from_mock(value: Option<MockValue>) -> Self {
+                    match value {
+                        Some(MockValue::Ptr(value)) => value as _,
+                        _ => std::ptr::null_mut(),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_mock_return!(Int: i32, u32, isize, i64, usize);
+impl_mock_return!(Float: f32, f64);
+impl_mock_return!(Ptr: *mut c_void, mbStringPtr);
+
+impl MockReturn for *const mbSlist {
+    fn from_mock(value: Option<MockValue>) -> Self {
+        match value {
+            Some(MockValue::Ptr(value)) => value as _,
+            _ => std::ptr::null(),
+        }
+    }
+}
+
+impl MockReturn for *const c_char {
+    /// Strings default to an empty string instead of null.
+    fn from_mock(value: Option<MockValue>) -> Self {
+        match value {
+            Some(MockValue::Ptr(value)) => value as _,
+            _ => c"".as_ptr(),
+        }
+    }
+}
+
+/// A call recorded by `MockBackend`.
+#[derive(Debug, Clone)]
+pub struct MockCall {
+    /// The name of the `mb*` function.
+    pub name: &'static str,
+    /// The arguments in declaration order.
+    pub args: Vec<MockValue>,
+}
+
+type Responder = Arc<dyn Fn(&[MockValue]) -> Option<MockValue> + Send + Sync>;
+
+/// The nodes of a list returned to the caller, pointing into `MockState::strings`.
+struct MockList {
+    _nodes: Vec<mbSlist>,
+}
+
+// The nodes are only read by the caller, and never through the state.
+unsafe impl Send for MockList {}
+unsafe impl Sync for MockList {}
+
+#[derive(Default)]
+struct MockState {
+    calls: Mutex<Vec<MockCall>>,
+    responders: Mutex<HashMap<&'static str, Responder>>,
+    strings: Mutex<Vec<CString>>,
+    lists: Mutex<Vec<MockList>>,
+    destroyed: Mutex<HashSet<i64>>,
+    unsupported: Mutex<HashSet<String>>,
+    next_id: AtomicIsize,
+}
+
+/// An in-process fake backend which records every call and lets tests fire callbacks.
+/// Requires the `mock` feature.
+///
+/// Clone the backend before installing it with `app::init_with_backend`, the clones share the
+/// same state.
+///
+/// ```
+/// use miniblink::{app, backend::MockBackend, webview::WebView};
+///
+/// let mock = MockBackend::new();
+/// app::init_with_backend(mock.clone());
+///
+/// let view = WebView::default();
+/// view.load_url("https://miniblink.net/");
+/// let call = mock.calls_to("mbLoadURL").pop().unwrap();
+/// assert_eq!(call.args[1].as_str(), Some("https://miniblink.net/"));
+/// ```
+#[derive(Clone)]
+pub struct MockBackend {
+    state: Arc<MockState>,
+}
+
+impl MockBackend {
+    /// Create a new mock backend.
+    ///
+    /// Webview creation returns unique ids, `mbCallUiThreadSync` runs the task immediately and
+    /// `mbCreateString` keeps the string alive for `mbGetString`. Everything else returns zero,
+    /// null or an empty string unless a responder is set.
+    pub fn new() -> Self {
+        let mock = Self {
+            state: Arc::new(MockState::default()),
+        };
+        mock.respond_defaults();
+        mock
+    }
+
+    /// Forget the recorded calls, the responders and the unsupported functions, like a new
+    /// backend. Webviews created before stop receiving the callbacks fired by the mock.
+    pub fn reset(&self) {
+        self.state.calls.lock().unwrap().clear();
+        self.state.responders.lock().unwrap().clear();
+        self.state.unsupported.lock().unwrap().clear();
+        self.respond_defaults();
+    }
+
+    fn respond_defaults(&self) {
+        let state = Arc::downgrade(&self.state);
+        let create_webview = move |_: &[MockValue]| {
+            let state = state.upgrade()?;
+            let id = state.next_id.fetch_add(1, Ordering::SeqCst) + 1;
+            Some(MockValue::Int(id as i64))
+        };
+        self.respond("mbCreateWebView", create_webview.clone());
+        self.respond("mbCreateWebWindow", create_webview);
+
+        self.respond("mbCallUiThreadSync", |args| {
+            let callback = args[0].callback::<mbThreadCallback>()??;
+            let param1 = args[1].as_ptr()? as *mut c_void;
+            let param2 = args[2].as_ptr()? as *mut c_void;
+            unsafe { callback(param1, param2) };
+            None
+        });
+
+        self.respond("mbCreateString", |args| {
+            args[0].as_str().map(MockValue::from)
+        });
+        self.respond("mbGetString", |args| args[0].as_ptr().map(MockValue::Ptr));
+        self.respond("mbGetStringLen", |args| {
+            let ptr = args[0].as_ptr().filter(|ptr| *ptr != 0)?;
+            let value = unsafe { CStr::from_ptr(ptr as *const c_char) };
+            Some(MockValue::Int(value.to_bytes().len() as i64))
+        });
+
+        let state = Arc::downgrade(&self.state);
+        self.respond("mbDestroyWebView", move |args| {
+            let state = state.upgrade()?;
+            state.destroyed.lock().unwrap().insert(args[0].as_int()?);
+            None
+        });
+    }
+
+    /// Set the responder of the `mb*` function `name`, replacing the previous one.
+    ///
+    /// The responder receives the call arguments and returns the value passed back to the wrapper.
+    /// A returned `MockValue::Str` is kept alive by the mock and handed out as a `utf8` pointer.
+    pub fn respond<F>(&self, name: &'static str, responder: F)
+    where
+        F: Fn(&[MockValue]) -> Option<MockValue> + Send + Sync + 'static,
+    {
+        self.state
+            .responders
+            .lock()
+            .unwrap()
+            .insert(name, Arc::new(responder));
+    }
+
+    /// Report the `mb*` function `name` as not exported, like an older `mb.dll` would.
+    ///
+    /// ```
+    /// use miniblink::{app, backend::MockBackend};
+    ///
+    /// let mock = MockBackend::new();
+    /// mock.unsupported("mbSetZoomFactor");
+    /// app::init_with_backend(mock.clone());
+    /// assert!(!app::capabilities().unwrap().has("mbSetZoomFactor"));
+    /// ```
+    pub fn unsupported(&self, name: &str) {
+        self.state
+            .unsupported
+            .lock()
+            .unwrap()
+            .insert(name.to_string());
+    }
+
+    pub(crate) fn is_supported(&self, name: &str) -> bool {
+        !self.state.unsupported.lock().unwrap().contains(name)
+    }
+
+    /// Get all recorded calls.
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.state.calls.lock().unwrap().clone()
+    }
+
+    /// Get the recorded calls to the `mb*` function `name`.
+    pub fn calls_to(&self, name: &str) -> Vec<MockCall> {
+        self.state
+            .calls
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|call| call.name == name)
+            .cloned()
+            .collect()
+    }
+
+    /// Clear the recorded calls. Registered callbacks are kept.
+    pub fn clear_calls(&self) {
+        self.state.calls.lock().unwrap().retain(|call| {
+            call.args
+                .iter()
+                .any(|arg| matches!(arg, MockValue::Callback(_)))
+        });
+    }
+
+    /// Get the callback and its param most recently registered through the setter `name`, such as
+    /// `mbOnURLChanged`, for the webview.
+    ///
+    /// Returns `None` if nothing is registered or the webview is destroyed.
+    pub fn callback<C>(&self, webview: mbWebView, name: &str) -> Option<(C, *mut c_void)>
+    where
+        C: Copy + 'static,
+    {
+        let webview = webview as i64;
+        if self.state.destroyed.lock().unwrap().contains(&webview) {
+            return None;
+        }
+        let calls = self.state.calls.lock().unwrap();
+        let call = calls
+            .iter()
+            .rev()
+            .find(|call| call.name == name && call.args[0].as_int() == Some(webview))?;
+        let callback = call.args[1].callback::<C>()?;
+        let param = call.args[2].as_ptr()? as *mut c_void;
+        Some((callback, param))
+    }
+
+    /// Fire the `mbOnURLChanged` callback.
+    pub fn fire_url_changed(
+        &self,
+        webview: mbWebView,
+        url: &str,
+        can_go_back: bool,
+        can_go_forward: bool,
+    ) -> Option<()> {
+        let (callback, param) = self.callback::<mbURLChangedCallback>(webview, "mbOnURLChanged")?;
+        let url = CString::new(url).ok()?;
+        unsafe {
+            callback?(
+                webview,
+                param,
+                url.as_ptr(),
+                can_go_back as _,
+                can_go_forward as _,
+            )
+        };
+        Some(())
+    }
+
+    /// Fire the `mbOnTitleChanged` callback.
+    pub fn fire_title_changed(&self, webview: mbWebView, title: &str) -> Option<()> {
+        let (callback, param) =
+            self.callback::<mbTitleChangedCallback>(webview, "mbOnTitleChanged")?;
+        let title = CString::new(title).ok()?;
+        unsafe { callback?(webview, param, title.as_ptr()) };
+        Some(())
+    }
+
+    /// Fire the `mbOnNavigation` callback. Returns whether the navigation continues.
+    pub fn fire_navigation(
+        &self,
+        webview: mbWebView,
+        navigation_type: c_int,
+        url: &str,
+    ) -> Option<bool> {
+        let (callback, param) = self.callback::<mbNavigationCallback>(webview, "mbOnNavigation")?;
+        let url = CString::new(url).ok()?;
+        let result = unsafe { callback?(webview, param, navigation_type, url.as_ptr()) };
+        Some(result != 0)
+    }
+
+    /// Fire the `mbOnDocumentReady` callback. The frame is an opaque handle chosen by the test.
+    pub fn fire_document_ready(&self, webview: mbWebView, frame: usize) -> Option<()> {
+        let (callback, param) =
+            self.callback::<mbDocumentReadyCallback>(webview, "mbOnDocumentReady")?;
+        unsafe { callback?(webview, param, frame as mbWebFrameHandle) };
+        Some(())
+    }
+
+    /// Fire the `mbOnLoadingFinish` callback. The result is one of `miniblink_sys::MB_LOADING_*`.
+    pub fn fire_loading_finish(
+        &self,
+        webview: mbWebView,
+        frame: usize,
+        url: &str,
+        result: c_int,
+    ) -> Option<()> {
+        self.fire_loading_finish_inner(webview, frame, url, result, None)
+    }
+
+    /// Fire the `mbOnLoadingFinish` callback with a failure reason.
+    pub fn fire_loading_finish_with_reason(
+        &self,
+        webview: mbWebView,
+        frame: usize,
+        url: &str,
+        result: c_int,
+        reason: &str,
+    ) -> Option<()> {
+        self.fire_loading_finish_inner(webview, frame, url, result, Some(reason))
+    }
+
+    fn fire_loading_finish_inner(
+        &self,
+        webview: mbWebView,
+        frame: usize,
+        url: &str,
+        result: c_int,
+        reason: Option<&str>,
+    ) -> Option<()> {
+        let (callback, param) =
+            self.callback::<mbLoadingFinishCallback>(webview, "mbOnLoadingFinish")?;
+        let url = CString::new(url).ok()?;
+        let reason = reason.map(CString::new).transpose().ok()?;
+        unsafe {
+            callback?(
+                webview,
+                param,
+                frame as mbWebFrameHandle,
+                url.as_ptr(),
+                result,
+                reason.as_ref().map_or(std::ptr::null(), |x| x.as_ptr()),
+            )
+        };
+        Some(())
+    }
+
+    /// Fire the `mbOnJsQuery` callback. The response is sent through `mbResponseQuery`.
+    pub fn fire_js_query(
+        &self,
+        webview: mbWebView,
+        query_id: i64,
+        custom_message: c_int,
+        request: &str,
+    ) -> Option<()> {
+        let (callback, param) = self.callback::<mbJsQueryCallback>(webview, "mbOnJsQuery")?;
+        let request = CString::new(request).ok()?;
+        unsafe {
+            callback?(
+                webview,
+                param,
+                std::ptr::null_mut(),
+                query_id,
+                custom_message,
+                request.as_ptr(),
+            )
+        };
+        Some(())
+    }
+
+    /// Fire the `mbOnDidCreateScriptContext` callback. World zero is the main world.
+    pub fn fire_did_create_script_context(
+        &self,
+        webview: mbWebView,
+        frame: usize,
+        world_id: c_int,
+    ) -> Option<()> {
+        let (callback, param) = self
+            .callback::<mbDidCreateScriptContextCallback>(webview, "mbOnDidCreateScriptContext")?;
+        unsafe {
+            callback?(
+                webview,
+                param,
+                frame as mbWebFrameHandle,
+                std::ptr::null_mut(),
+                0,
+                world_id,
+            )
+        };
+        Some(())
+    }
+
+    /// Fire the `mbOnWillReleaseScriptContext` callback.
+    pub fn fire_will_release_script_context(
+        &self,
+        webview: mbWebView,
+        frame: usize,
+        world_id: c_int,
+    ) -> Option<()> {
+        let (callback, param) = self.callback::<mbWillReleaseScriptContextCallback>(
+            webview,
+            "mbOnWillReleaseScriptContext",
+        )?;
+        unsafe {
+            callback?(
+                webview,
+                param,
+                frame as mbWebFrameHandle,
+                std::ptr::null_mut(),
+                world_id,
+            )
+        };
+        Some(())
+    }
+
+    /// Fire the `mbOnConsole` callback without a stack trace. The level is one of
+    /// `miniblink_sys::mbLevel*`.
+    pub fn fire_console(
+        &self,
+        webview: mbWebView,
+        level: c_int,
+        message: &str,
+        source: &str,
+        line: u32,
+    ) -> Option<()> {
+        let (callback, param) = self.callback::<mbConsoleCallback>(webview, "mbOnConsole")?;
+        let message = CString::new(message).ok()?;
+        let source = CString::new(source).ok()?;
+        unsafe {
+            callback?(
+                webview,
+                param,
+                level,
+                message.as_ptr(),
+                source.as_ptr(),
+                line,
+                std::ptr::null(),
+            )
+        };
+        Some(())
+    }
+
+    /// Fire the `mbOnLoadUrlBegin` callback for the net job `job`. Returns the result of the
+    /// callback.
+    pub fn fire_load_url_begin(&self, webview: mbWebView, url: &str, job: usize) -> Option<bool> {
+        let (callback, param) =
+            self.callback::<mbLoadUrlBeginCallback>(webview, "mbOnLoadUrlBegin")?;
+        let url = CString::new(url).ok()?;
+        let result = unsafe { callback?(webview, param, url.as_ptr(), job as _) };
+        Some(result != 0)
+    }
+
+    /// Fire the `mbOnLoadUrlHeadersReceived` callback for the net job `job`.
+    pub fn fire_load_url_headers_received(
+        &self,
+        webview: mbWebView,
+        url: &str,
+        job: usize,
+    ) -> Option<()> {
+        let (callback, param) = self
+            .callback::<mbLoadUrlHeadersReceivedCallback>(webview, "mbOnLoadUrlHeadersReceived")?;
+        let url = CString::new(url).ok()?;
+        unsafe { callback?(webview, param, url.as_ptr(), job as _) };
+        Some(())
+    }
+
+    /// Fire the `mbOnClose` callback. Returns whether the window closes.
+    pub fn fire_close(&self, webview: mbWebView) -> Option<bool> {
+        let (callback, param) = self.callback::<mbCloseCallback>(webview, "mbOnClose")?;
+        let result = unsafe { callback?(webview, param, std::ptr::null_mut()) };
+        Some(result != 0)
+    }
+
+    /// Fire the pending `mbGetCookie` callback with the cookie, or a failed state if `None`.
+    pub fn fire_get_cookie(&self, webview: mbWebView, cookie: Option<&str>) -> Option<()> {
+        let (callback, param) = self.callback::<mbGetCookieCallback>(webview, "mbGetCookie")?;
+        let state = if cookie.is_some() { 0 } else { 1 };
+        let cookie = CString::new(cookie.unwrap_or_default()).ok()?;
+        unsafe { callback?(webview, param, state, cookie.as_ptr()) };
+        Some(())
+    }
+
+    /// Fire the pending `mbGetSource` or `mbUtilSerializeToMHTML` callback, selected by `name`.
+    pub fn fire_get_source(&self, webview: mbWebView, name: &str, content: &str) -> Option<()> {
+        let (callback, param) = self.callback::<mbGetSourceCallback>(webview, name)?;
+        let content = CString::new(content).ok()?;
+        unsafe { callback?(webview, param, content.as_ptr()) };
+        Some(())
+    }
+
+    /// Fire the pending `mbGetContentAsMarkup` callback.
+    pub fn fire_get_content_as_markup(&self, webview: mbWebView, content: &str) -> Option<()> {
+        let (callback, param) =
+            self.callback::<mbGetContentAsMarkupCallback>(webview, "mbGetContentAsMarkup")?;
+        unsafe { callback?(webview, param, content.as_ptr() as _, content.len()) };
+        Some(())
+    }
+
+    pub(crate) fn dispatch(&self, name: &'static str, args: Vec<MockValue>) -> Option<MockValue> {
+        self.state.calls.lock().unwrap().push(MockCall {
+            name,
+            args: args.clone(),
+        });
+        let responder = self.state.responders.lock().unwrap().get(name).cloned()?;
+        match responder(&args)? {
+            MockValue::Str(value) => {
+                let value = CString::new(value).unwrap_or_default();
+                let ptr = value.as_ptr() as usize;
+                self.state.strings.lock().unwrap().push(value);
+                Some(MockValue::Ptr(ptr))
+            }
+            MockValue::List(items) => {
+                let items: Vec<CString> = items
+                    .into_iter()
+                    .map(|x| CString::new(x).unwrap_or_default())
+                    .collect();
+                let mut nodes: Vec<mbSlist> = items
+                    .iter()
+                    .map(|x| mbSlist {
+                        data: x.as_ptr() as _,
+                        next: std::ptr::null_mut(),
+                    })
+                    .collect();
+                let head = nodes.as_mut_ptr();
+                for i in 1..nodes.len() {
+                    nodes[i - 1].next = unsafe { head.add(i) };
+                }
+                let ptr = if nodes.is_empty() { 0 } else { head as usize };
+                self.state.strings.lock().unwrap().extend(items);
+                self.state
+                    .lists
+                    .lock()
+                    .unwrap()
+                    .push(MockList { _nodes: nodes });
+                Some(MockValue::Ptr(ptr))
+            }
+            value => Some(value),
+        }
+    }
+}
+
+impl Default for MockBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<&str> for MockValue {
+    fn from(value: &str) -> Self {
+        MockValue::Str(value.to_string())
+    }
+}
+
+impl From<String> for MockValue {
+    fn from(value: String) -> Self {
+        MockValue::Str(value)
+    }
+}
+
+impl From<i64> for MockValue {
+    fn from(value: i64) -> Self {
+        MockValue::Int(value)
+    }
+}
+
+impl From<bool> for MockValue {
+    fn from(value: bool) -> Self {
+        MockValue::Int(value as i64)
+    }
+}
+
+impl From<f64> for MockValue {
+    fn from(value: f64) -> Self {
+        MockValue::Float(value)
+    }
+}
@@ -0,0 +1,225 @@
+use std::ffi::{c_char, c_int, c_uint, c_void};
+
+use miniblink_sys::*;
+
+#[cfg(feature = "mock")]
+mod mock;
+
+#[cfg(feature = "mock")]
+pub use mock::*;
+
+macro_rules! define_backend {
+    (
+        $(
+            fn $name:ident($($arg:ident: $ty:ty),* $(,)?) $(-> $ret:ty)?;
+        )*
+    ) => {
+        /// The engine entry points used by the safe wrapper.
+        ///
+        /// Each method mirrors the function of the same name in `miniblink_sys::Library`.
+        /// The default implementation is `miniblink_sys::Library`, and `MockBackend`, behind the
+        /// `mock` feature, runs the wrapper without `mb.dll`.
+        #[allow(
+            non_snake_case,
+            missing_docs,
+            clippy::missing_safety_doc,
+            clippy::too_many_arguments
+        )]
+        pub trait Backend: Send + Sync + 'static {
+            $(
+                unsafe fn $name(&self, $($arg: $ty),*) $(-> $ret)?;
+            )*
+
+            /// Check if the backend provides the `mb*` function `name`.
+            fn has_symbol(&self, name: &str) -> bool {
+                let _ = name;
+                true
+            }
+
+            /// Get the underlying library if the backend is `miniblink_sys::Library`.
+            fn as_library(&self) -> Option<&Library> {
+                None
+            }
+        }
+
+        #[allow(non_snake_case)]
+        impl Backend for Library {
+            $(
+                unsafe fn $name(&self, $($arg: $ty),*) $(-> $ret)? {
+                    Library::$name(self, $($arg),*)
+                }
+            )*
+
+            fn has_symbol(&self, name: &str) -> bool {
+                self.has(name)
+            }
+
+            fn as_library(&self) -> Option<&Library> {
+                Some(self)
+            }
+        }
+
+        #[cfg(feature = "mock")]
+        #[allow(non_snake_case)]
+        impl Backend for MockBackend {
+            $(
+                unsafe fn $name(&self, $($arg: $ty),*) $(-> $ret)? {
+                    let args = vec![$(MockValue::from_arg($arg)),*];
+                    MockReturn::from_mock(self.dispatch(stringify!($name), args))
+                }
+            )*
+
+            fn has_symbol(&self, name: &str) -> bool {
+                self.is_supported(name)
+            }
+        }
+
+        /// The `mb*` functions used by the safe wrapper, each known to `miniblink_sys::Library::has`.
+        pub const SYMBOLS: &[&str] = &[$(stringify!($name)),*];
+    };
+}
+
+define_backend! {
+    fn mbCallUiThreadSync(callback: mbThreadCallback, param1: *mut c_void, param2: *mut c_void);
+    fn mbCanGoBack(web_view: mbWebView, callback: mbCanGoBackForwardCallback, param: *mut c_void);
+    fn mbCanGoBackOrForward(web_view: mbWebView, is_go_back: BOOL) -> BOOL;
+    fn mbCanGoForward(web_view: mbWebView, callback: mbCanGoBackForwardCallback, param: *mut c_void);
+    fn mbClearCookie(web_view: mbWebView);
+    fn mbCreateString(str_: *const utf8, length: usize) -> mbStringPtr;
+    fn mbCreateWebView() -> mbWebView;
+    fn mbCreateWebWindow(type_: mbWindowType, parent: HWND, x: c_int, y: c_int, width: c_int, height: c_int) -> mbWebView;
+    fn mbDeleteString(str_: mbStringPtr);
+    fn mbDestroyWebView(web_view: mbWebView);
+    fn mbEditorCopy(web_view: mbWebView);
+    fn mbEditorCut(web_view: mbWebView);
+    fn mbEditorDelete(web_view: mbWebView);
+    fn mbEditorPaste(web_view: mbWebView);
+    fn mbEditorRedo(web_view: mbWebView);
+    fn mbEditorSelectAll(web_view: mbWebView);
+    fn mbEditorUnSelect(web_view: mbWebView);
+    fn mbEditorUndo(web_view: mbWebView);
+    fn mbEnableHighDPISupport();
+    fn mbExitMessageLoop();
+    fn mbFireKeyDownEvent(web_view: mbWebView, virtual_key_code: c_uint, flags: c_uint, system_key: BOOL) -> BOOL;
+    fn mbFireKeyPressEvent(web_view: mbWebView, char_code: c_uint, flags: c_uint, system_key: BOOL) -> BOOL;
+    fn mbFireKeyUpEvent(web_view: mbWebView, virtual_key_code: c_uint, flags: c_uint, system_key: BOOL) -> BOOL;
+    fn mbFireMouseEvent(web_view: mbWebView, message: c_uint, x: c_int, y: c_int, flags: c_uint) -> BOOL;
+    fn mbFireMouseWheelEvent(web_view: mbWebView, x: c_int, y: c_int, delta: c_int, flags: c_uint) -> BOOL;
+    fn mbGetCaretRect(webview_handle: mbWebView, r: *mut mbRect);
+    fn mbGetContentAsMarkup(web_view: mbWebView, calback: mbGetContentAsMarkupCallback, param: *mut c_void, frame_id: mbWebFrameHandle);
+    fn mbGetContentHeight(web_view: mbWebView) -> c_int;
+    fn mbGetContentWidth(web_view: mbWebView) -> c_int;
+    fn mbGetCookie(web_view: mbWebView, callback: mbGetCookieCallback, param: *mut c_void);
+    fn mbGetCookieOnBlinkThread(web_view: mbWebView) -> *const utf8;
+    fn mbGetCursorInfoType(web_view: mbWebView) -> c_int;
+    fn mbGetGlobalExecByFrame(web_view: mbWebView, frame_id: mbWebFrameHandle) -> mbJsExecState;
+    fn mbGetJsValueType(es: mbJsExecState, v: mbJsValue) -> mbJsType;
+    fn mbGetNavigateIndex(web_view: mbWebView) -> c_int;
+    fn mbGetParentWebFrameHandle(web_view: mbWebView, frame: mbWebFrameHandle) -> mbWebFrameHandle;
+    fn mbGetPlatformWindowHandle(web_view: mbWebView) -> *mut c_void;
+    fn mbGetSize(web_view: mbWebView, rc: *mut mbRect);
+    fn mbGetSource(web_view: mbWebView, calback: mbGetSourceCallback, param: *mut c_void);
+    fn mbGetSourceSync(web_view: mbWebView) -> mbStringPtr;
+    fn mbGetString(str_: mbStringPtr) -> *const utf8;
+    fn mbGetStringLen(str_: mbStringPtr) -> usize;
+    fn mbGetTitle(web_view: mbWebView) -> *const utf8;
+    fn mbGetUrl(web_view: mbWebView) -> *const utf8;
+    fn mbGetWindowRect(web_view: mbWebView, rc: *mut mbRect) -> BOOL;
+    fn mbGetZoomFactor(web_view: mbWebView) -> f32;
+    fn mbGoBack(web_view: mbWebView);
+    fn mbGoForward(web_view: mbWebView);
+    fn mbGoToIndex(web_view: mbWebView, index: c_int);
+    fn mbGoToOffset(web_view: mbWebView, offset: c_int);
+    fn mbInit(settings: *const mbSettings);
+    fn mbInsertCSSByFrame(web_view: mbWebView, frame_id: mbWebFrameHandle, css_text: *const utf8);
+    fn mbIsLoading(web_view: mbWebView) -> BOOL;
+    fn mbIsMainFrame(web_view: mbWebView, frame_id: mbWebFrameHandle) -> BOOL;
+    fn mbJsToBoolean(es: mbJsExecState, v: mbJsValue) -> BOOL;
+    fn mbJsToDouble(es: mbJsExecState, v: mbJsValue) -> f64;
+    fn mbJsToString(es: mbJsExecState, v: mbJsValue) -> *const utf8;
+    fn mbJsToWebFrameHandle(es: mbJsExecState, v: mbJsValue) -> mbWebFrameHandle;
+    fn mbJsValueAddRef(es: mbJsExecState, v: mbJsValue);
+    fn mbJsValueDeref(es: mbJsExecState, v: mbJsValue);
+    fn mbKillFocus(web_view: mbWebView);
+    fn mbLoadHtmlWithBaseUrl(web_view: mbWebView, html: *const utf8, base_url: *const utf8);
+    fn mbLoadURL(web_view: mbWebView, url: *const utf8);
+    fn mbMoveToCenter(web_view: mbWebView);
+    fn mbMoveWindow(web_view: mbWebView, x: c_int, y: c_int, w: c_int, h: c_int);
+    fn mbNavigateAtIndex(web_view: mbWebView, index: c_int);
+    fn mbNetCancelRequest(job_ptr: mbNetJob);
+    fn mbNetChangeRequestUrl(job_ptr: mbNetJob, url: *const c_char);
+    fn mbNetContinueJob(job_ptr: mbNetJob);
+    fn mbNetGetMIMEType(job_ptr: mbNetJob) -> *const c_char;
+    fn mbNetGetRawHttpHeadInBlinkThread(job_ptr: mbNetJob) -> *const mbSlist;
+    fn mbNetGetRawResponseHeadInBlinkThread(job_ptr: mbNetJob) -> *const mbSlist;
+    fn mbNetGetRequestMethod(job_ptr: mbNetJob) -> mbRequestType;
+    fn mbNetHoldJobToAsynCommit(job_ptr: mbNetJob);
+    fn mbNetHookRequest(job_ptr: mbNetJob);
+    fn mbNetSetData(job_ptr: mbNetJob, buf: *mut c_void, len: c_int);
+    fn mbNetSetHTTPHeaderField(job_ptr: mbNetJob, key: *const WCHAR, value: *const WCHAR, response: BOOL);
+    fn mbNetSetMIMEType(job_ptr: mbNetJob, type_: *const c_char);
+    fn mbOnAlertBox(web_view: mbWebView, callback: mbAlertBoxCallback, param: *mut c_void);
+    fn mbOnClose(web_view: mbWebView, callback: mbCloseCallback, param: *mut c_void) -> BOOL;
+    fn mbOnConfirmBox(web_view: mbWebView, callback: mbConfirmBoxCallback, param: *mut c_void);
+    fn mbOnConsole(web_view: mbWebView, callback: mbConsoleCallback, param: *mut c_void);
+    fn mbOnCreateView(web_view: mbWebView, callback: mbCreateViewCallback, param: *mut c_void);
+    fn mbOnDestroy(web_view: mbWebView, callback: mbDestroyCallback, param: *mut c_void) -> BOOL;
+    fn mbOnDidCreateScriptContext(web_view: mbWebView, callback: mbDidCreateScriptContextCallback, callback_param: *mut c_void);
+    fn mbOnDocumentReady(web_view: mbWebView, callback: mbDocumentReadyCallback, param: *mut c_void);
+    fn mbOnDownload(web_view: mbWebView, callback: mbDownloadCallback, param: *mut c_void);
+    fn mbOnJsQuery(web_view: mbWebView, callback: mbJsQueryCallback, param: *mut c_void);
+    fn mbOnLoadingFinish(web_view: mbWebView, callback: mbLoadingFinishCallback, param: *mut c_void);
+    fn mbOnLoadUrlBegin(web_view: mbWebView, callback: mbLoadUrlBeginCallback, callback_param: *mut c_void);
+    fn mbOnLoadUrlEnd(web_view: mbWebView, callback: mbLoadUrlEndCallback, callback_param: *mut c_void);
+    fn mbOnLoadUrlFail(web_view: mbWebView, callback: mbLoadUrlFailCallback, callback_param: *mut c_void);
+    fn mbOnLoadUrlFinish(web_view: mbWebView, callback: mbLoadUrlFinishCallback, callback_param: *mut c_void);
+    fn mbOnLoadUrlHeadersReceived(web_view: mbWebView, callback: mbLoadUrlHeadersReceivedCallback, callback_param: *mut c_void);
+    fn mbOnNavigation(web_view: mbWebView, callback: mbNavigationCallback, param: *mut c_void);
+    fn mbOnPromptBox(web_view: mbWebView, callback: mbPromptBoxCallback, param: *mut c_void);
+    fn mbOnTitleChanged(web_view: mbWebView, callback: mbTitleChangedCallback, callback_param: *mut c_void);
+    fn mbOnURLChanged(web_view: mbWebView, callback: mbURLChangedCallback, callback_param: *mut c_void);
+    fn mbOnWillReleaseScriptContext(web_view: mbWebView, callback: mbWillReleaseScriptContextCallback, callback_param: *mut c_void);
+    fn mbPerformCookieCommand(web_view: mbWebView, command: mbCookieCommand);
+    fn mbReload(web_view: mbWebView);
+    fn mbResize(web_view: mbWebView, w: c_int, h: c_int);
+    fn mbResponseQuery(web_view: mbWebView, query_id: i64, custom_msg: c_int, response: *const utf8);
+    fn mbRunJs(web_view: mbWebView, frame_id: mbWebFrameHandle, script: *const utf8, is_in_closure: BOOL, callback: mbRunJsCallback, param: *mut c_void, unuse: *mut c_void);
+    fn mbRunJsSync(web_view: mbWebView, frame_id: mbWebFrameHandle, script: *const utf8, is_in_closure: BOOL) -> mbJsValue;
+    fn mbRunMessageLoop();
+    fn mbSetContextMenuEnabled(web_view: mbWebView, b: BOOL);
+    fn mbSetCookie(web_view: mbWebView, url: *const utf8, cookie: *const utf8);
+    fn mbSetCookieEnabled(web_view: mbWebView, enable: BOOL);
+    fn mbSetCookieJarFullPath(web_view: mbWebView, path: *const WCHAR);
+    fn mbSetCookieJarPath(web_view: mbWebView, path: *const WCHAR);
+    fn mbSetCspCheckEnable(web_view: mbWebView, b: BOOL);
+    fn mbSetDebugConfig(web_view: mbWebView, debug_string: *const c_char, param: *const c_char);
+    fn mbSetDiskCacheEnabled(web_view: mbWebView, enable: BOOL);
+    fn mbSetDiskCacheLevel(web_view: mbWebView, level: c_int);
+    fn mbSetDiskCacheLimit(web_view: mbWebView, limit: usize);
+    fn mbSetDiskCacheLimitDisk(web_view: mbWebView, limit: usize);
+    fn mbSetDiskCachePath(web_view: mbWebView, path: *const WCHAR);
+    fn mbSetDragDropEnable(web_view: mbWebView, b: BOOL);
+    fn mbSetDragEnable(web_view: mbWebView, b: BOOL);
+    fn mbSetFocus(web_view: mbWebView);
+    fn mbSetHandle(web_view: mbWebView, wnd: HWND);
+    fn mbSetHandleOffset(web_view: mbWebView, x: c_int, y: c_int);
+    fn mbSetHeadlessEnabled(web_view: mbWebView, b: BOOL);
+    fn mbSetLocalStorageFullPath(web_view: mbWebView, path: *const WCHAR);
+    fn mbSetMemoryCacheEnable(web_view: mbWebView, b: BOOL);
+    fn mbSetMouseEnabled(web_view: mbWebView, b: BOOL);
+    fn mbSetNavigationToNewWindowEnable(web_view: mbWebView, b: BOOL);
+    fn mbSetNodeJsEnable(web_view: mbWebView, b: BOOL);
+    fn mbSetNpapiPluginsEnabled(web_view: mbWebView, b: BOOL);
+    fn mbSetProxy(web_view: mbWebView, proxy: *const mbProxy);
+    fn mbSetResourceGc(web_view: mbWebView, interval_sec: c_int);
+    fn mbSetSystemTouchEnabled(web_view: mbWebView, b: BOOL);
+    fn mbSetTouchEnabled(web_view: mbWebView, b: BOOL);
+    fn mbSetUserAgent(web_view: mbWebView, user_agent: *const utf8);
+    fn mbSetWindowTitle(web_view: mbWebView, title: *const utf8);
+    fn mbSetZoomFactor(web_view: mbWebView, factor: f32);
+    fn mbShowWindow(web_view: mbWebView, show: c_int);
+    fn mbStopLoading(web_view: mbWebView);
+    fn mbUninit();
+    fn mbUtilSerializeToMHTML(web_view: mbWebView, calback: mbGetSourceCallback, param: *mut c_void);
+    fn mbWebFrameGetMainFrame(web_view: mbWebView) -> mbWebFrameHandle;
+}
@@ -1,4 +1,11 @@
-use crate::{net_job::NetJob, params::*, types::*, webview::WebView};
+use std::sync::Weak;
+
+use crate::{
+    net_job::NetJob,
+    params::*,
+    types::*,
+    webview::{WebView, WebViewInner},
+};
 
 macro_rules! define_callback {
     (
@@ -21,6 +28,14 @@ define_callback!(
     OnDownload: (&WebView, &DownloadParameters) -> bool;
     /// Triggered when the main frame has finished loading.
     OnDocumentReady: (&WebView, &WebFrameHandle);
+    /// Triggered when a frame finished loading, failed or is canceled.
+    OnLoadingFinish: (&WebView, &LoadingFinishParameters);
+    /// Triggered when the page writes to the console.
+    OnConsole: (&WebView, &ConsoleMessage);
+    /// Triggered when a script context is created, before the page scripts run.
+    OnDidCreateScriptContext: (&WebView, &ScriptContextParameters);
+    /// Triggered when a script context is about to be released.
+    OnWillReleaseScriptContext: (&WebView, &ScriptContextParameters);
     /// Triggered before navigating to a new URL.
     OnNavigation: (&WebView, &NavigationParameters) -> bool;
     /// Triggered when the page requests a new window/webview.
@@ -67,5 +82,101 @@ define_callback!(
     /// Triggered before a network request starts.
     OnLoadUrlBegin: (&WebView, &str, &NetJob) -> bool;
     /// Triggered after a network request finishes.
-    OnLoadUrlEnd: (&WebView, &str, &NetJob, &[u8])
+    OnLoadUrlEnd: (&WebView, &str, &NetJob, &[u8]);
+    /// Triggered when a network request fails.
+    OnLoadUrlFail: (&WebView, &str, &NetJob);
+    /// Triggered when a network request finishes, with the length of the received data.
+    OnLoadUrlFinish: (&WebView, &str, &NetJob, usize);
+    /// Triggered when the response headers of a network request are received.
+    OnLoadUrlHeadersReceived: (&WebView, &str, &NetJob)
 );
+
+/// A callback registered by one of the `on_*` methods of `WebView`.
+///
+/// Dropping the handle keeps the callback registered. Call `revoke` to unregister it, or
+/// `into_guard` to unregister it when the guard is dropped. Registering the same event again
+/// replaces and frees the previous callback, and its handle becomes inactive.
+///
+/// ```rust,no_run
+/// use miniblink::{app, webview::WebView};
+///
+/// app::init("./mb.dll").unwrap();
+/// let view = WebView::default();
+/// let guard = view.on_title_changed(|_, title| println!("{title}")).into_guard();
+/// // The callback is unregistered here.
+/// drop(guard);
+/// ```
+///
+/// The `on_*` methods of the events in `events` share the native callback with the listeners
+/// added by `WebView::subscribe`, and do not replace them.
+pub struct CallbackHandle {
+    pub(crate) webview: Weak<WebViewInner>,
+    pub(crate) setter: &'static str,
+    pub(crate) token: u64,
+}
+
+impl CallbackHandle {
+    /// Unregister the callback and free it.
+    ///
+    /// # Remarks
+    /// Does nothing if the callback was replaced or the webview is gone.
+    pub fn revoke(self) {
+        if let Some(inner) = self.webview.upgrade() {
+            inner.revoke_callback(self.setter, self.token);
+        }
+    }
+
+    /// Check if the callback is still registered.
+    pub fn is_registered(&self) -> bool {
+        self.webview
+            .upgrade()
+            .is_some_and(|inner| inner.has_callback(self.setter, self.token))
+    }
+
+    /// Convert into a guard which unregisters the callback when dropped.
+    pub fn into_guard(self) -> CallbackGuard {
+        CallbackGuard { handle: Some(self) }
+    }
+}
+
+/// A handle which refers to no callback, returned by the `on_*` methods of a destroyed webview.
+impl Default for CallbackHandle {
+    fn default() -> Self {
+        Self {
+            webview: Weak::new(),
+            setter: "",
+            token: 0,
+        }
+    }
+}
+
+impl std::fmt::Debug for CallbackHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CallbackHandle")
+            .field("setter", &self.setter)
+            .field("token", &self.token)
+            .finish()
+    }
+}
+
+/// Unregisters the callback when dropped. See `CallbackHandle::into_guard`.
+#[must_use = "the callback is unregistered when the guard is dropped"]
+#[derive(Debug)]
+pub struct CallbackGuard {
+    handle: Option<CallbackHandle>,
+}
+
+impl CallbackGuard {
+    /// Keep the callback registered and get back the handle.
+    pub fn into_handle(mut self) -> CallbackHandle {
+        self.handle.take().unwrap()
+    }
+}
+
+impl Drop for CallbackGuard {
+    fn drop(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            handle.revoke();
+        }
+    }
+}
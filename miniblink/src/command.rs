@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::Arc;
+
+use crate::error::{MBError, MBResult};
+use crate::events::{DocumentReady, Subscription};
+use crate::params::COMMAND_QUERY;
+use crate::webview::WebView;
+
+type CommandHandler = Arc<dyn Fn(&WebView, &str) -> Result<String, String> + Send>;
+
+/// The commands of a webview, and the init script injecting the prelude.
+#[derive(Default)]
+pub(crate) struct CommandRouter {
+    handlers: HashMap<String, CommandHandler>,
+    prelude: Option<Subscription>,
+}
+
+// The handlers are only called by the query shim, which runs on the UI thread.
+unsafe impl Send for CommandRouter {}
+
+/// The script defining `window.rust.invoke(name, args)`.
+///
+/// The request is the command name and the JSON arguments separated by a newline. Each call has
+/// its own `mbQuery` callback, so concurrent calls resolve independently.
+pub(crate) fn prelude() -> String {
+    format!(
+        r#"(function () {{
+    var rust = window.rust = window.rust || {{}};
+    if (rust.invoke) return;
+    rust.invoke = function (name, args) {{
+        return new Promise(function (resolve, reject) {{
+            var request = String(name) + "\n" + JSON.stringify(args === undefined ? null : args);
+            window.mbQuery({COMMAND_QUERY}, request, function (customMsg, response) {{
+                var value;
+                try {{ value = JSON.parse(response); }} catch (e) {{ value = response; }}
+                if (customMsg === {COMMAND_QUERY}) resolve(value);
+                else reject(new Error(value && value.message !== undefined ? value.message : String(value)));
+            }});
+        }});
+    }};
+}})();"#
+    )
+}
+
+/// Register the command, routing the command channel and injecting the prelude if needed.
+pub(crate) fn register<F>(webview: &WebView, name: &str, handler: F) -> MBResult<()>
+where
+    F: Fn(&WebView, &str) -> Result<String, String> + Send + 'static,
+{
+    if name.is_empty() || name.contains('\n') {
+        return Err(MBError::InvalidString);
+    }
+
+    let install = {
+        let mut router = webview.inner.commands.lock().unwrap();
+        router.handlers.insert(name.to_string(), Arc::new(handler));
+        router.prelude.is_none()
+    };
+
+    if install {
+        let result = webview
+            .try_route_query(COMMAND_QUERY, dispatch)
+            .and_then(
+                |_| match webview.subscribe_init_script(i32::MAX - 1, prelude()) {
+                    // Older libraries lack the script context callbacks.
+                    Err(MBError::Unsupported(_)) => {
+                        webview.try_subscribe::<DocumentReady, _>(i32::MAX - 1, |webview, frame| {
+                            let _ = webview.try_eval(*frame, &prelude(), true);
+                        })
+                    }
+                    result => result,
+                },
+            );
+        match result {
+            Ok(subscription) => webview.inner.commands.lock().unwrap().prelude = Some(subscription),
+            Err(error) => {
+                unregister(webview, name);
+                return Err(error);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Remove the command, and the command channel once no command is left.
+pub(crate) fn unregister(webview: &WebView, name: &str) {
+    let prelude = {
+        let mut router = webview.inner.commands.lock().unwrap();
+        router.handlers.remove(name);
+        match router.handlers.is_empty() {
+            true => router.prelude.take(),
+            false => None,
+        }
+    };
+    if let Some(prelude) = prelude {
+        prelude.unsubscribe();
+        webview.remove_query_handler(COMMAND_QUERY);
+    }
+}
+
+fn dispatch(webview: &WebView, request: &str) -> Result<String, String> {
+    let (name, args) = request.split_once('\n').unwrap_or((request, "null"));
+    let handler = webview
+        .inner
+        .commands
+        .lock()
+        .unwrap()
+        .handlers
+        .get(name)
+        .cloned()
+        .ok_or_else(|| format!("unknown command: {name}"))?;
+    catch_unwind(AssertUnwindSafe(|| handler(webview, args)))
+        .unwrap_or_else(|_| Err(format!("command panicked: {name}")))
+}
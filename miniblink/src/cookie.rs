@@ -0,0 +1,140 @@
+use std::path::PathBuf;
+use std::time::UNIX_EPOCH;
+
+use crate::error::{MBError, MBResult, UnwrapOrGone};
+use crate::types::{Cookie, CookieCommand, CookieJar};
+use crate::webview::WebView;
+
+/// The cookies of a webview. See `WebView::cookie_manager`.
+///
+/// # Remarks
+/// Miniblink does not export a function to visit the cookies, so they are read from the cookie
+/// jar after flushing it. Lines of the jar which are not valid cookies are skipped. The jar is
+/// the one set by `WebView::set_cookie_jar_path` or `WebView::set_cookie_jar_full_path`, or
+/// `cookies.dat` in the current directory.
+///
+/// ```rust,no_run
+/// use miniblink::{app, types::Cookie, webview::WebView};
+///
+/// app::init("./mb.dll").unwrap();
+/// let view = WebView::default();
+/// let cookies = view.cookie_manager();
+/// cookies.set(&Cookie::new("lang", "en", ".example.com"));
+/// let sid = cookies.get("https://www.example.com/", "sid");
+/// ```
+pub struct CookieManager {
+    webview: WebView,
+}
+
+impl CookieManager {
+    pub(crate) fn new(webview: &WebView) -> Self {
+        Self {
+            webview: WebView {
+                inner: webview.inner.clone(),
+            },
+        }
+    }
+
+    /// Get the cookie jar file miniblink writes to.
+    pub fn jar_path(&self) -> PathBuf {
+        match &*self.webview.inner.cookie_jar.lock().unwrap() {
+            Some(path) => path.clone(),
+            None => PathBuf::from("cookies.dat"),
+        }
+    }
+
+    /// Get the cookies accepted by `filter`. Expired cookies are skipped.
+    pub fn list<F>(&self, filter: F) -> Vec<Cookie>
+    where
+        F: Fn(&Cookie) -> bool,
+    {
+        self.try_list(filter).unwrap_or_gone()
+    }
+
+    /// Fallible version of `list`.
+    pub fn try_list<F>(&self, filter: F) -> MBResult<Vec<Cookie>>
+    where
+        F: Fn(&Cookie) -> bool,
+    {
+        let jar = self.flush()?;
+        let cookies = jar
+            .cookies
+            .into_iter()
+            .filter(|x| !x.is_expired() && filter(x))
+            .collect();
+        Ok(cookies)
+    }
+
+    /// Flush the cookies of miniblink and read the cookie jar. A missing jar is empty, and the
+    /// lines which are not valid cookies are skipped.
+    pub(crate) fn flush(&self) -> MBResult<CookieJar> {
+        self.webview
+            .try_perform_cookie_command(CookieCommand::FlushCookiesToFile)?;
+        match std::fs::read_to_string(self.jar_path()) {
+            Ok(text) => Ok(CookieJar::parse_lenient(&text)),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(CookieJar::default()),
+            Err(error) => Err(MBError::Io(error)),
+        }
+    }
+
+    /// Write the cookie jar and load it into miniblink.
+    pub(crate) fn reload(&self, jar: &CookieJar) -> MBResult<()> {
+        jar.write(self.jar_path())?;
+        self.webview
+            .try_perform_cookie_command(CookieCommand::ReloadCookiesFromFile)
+    }
+
+    /// Get the cookie named `name` sent with a request to `url`. The most specific path wins.
+    pub fn get(&self, url: &str, name: &str) -> Option<Cookie> {
+        self.try_get(url, name).unwrap_or_gone()
+    }
+
+    /// Fallible version of `get`.
+    pub fn try_get(&self, url: &str, name: &str) -> MBResult<Option<Cookie>> {
+        let cookies = self.try_list(|x| x.name == name && x.matches(url))?;
+        Ok(cookies.into_iter().max_by_key(|x| x.path.len()))
+    }
+
+    /// Add or replace the cookie.
+    pub fn set(&self, cookie: &Cookie) {
+        self.try_set(cookie).unwrap_or_gone()
+    }
+
+    /// Fallible version of `set`.
+    pub fn try_set(&self, cookie: &Cookie) -> MBResult<()> {
+        self.webview
+            .try_set_cookie(&cookie.url(), &cookie.try_to_set_cookie()?)
+    }
+
+    /// Delete the cookies named `name` of the domain, for all paths.
+    ///
+    /// # Remarks
+    /// A leading dot of `domain` is ignored, so `example.com` also deletes `.example.com` cookies.
+    pub fn delete(&self, domain: &str, name: &str) {
+        self.try_delete(domain, name).unwrap_or_gone()
+    }
+
+    /// Fallible version of `delete`.
+    pub fn try_delete(&self, domain: &str, name: &str) -> MBResult<()> {
+        let domain = domain.trim_start_matches('.');
+        let cookies =
+            self.try_list(|x| x.name == name && x.domain.trim_start_matches('.') == domain)?;
+        for mut cookie in cookies {
+            // curl drops a cookie replaced by an expired one.
+            cookie.expires = Some(UNIX_EPOCH);
+            self.try_set(&cookie)?;
+        }
+        Ok(())
+    }
+
+    /// Delete all session cookies.
+    pub fn clear_session(&self) {
+        self.try_clear_session().unwrap_or_gone()
+    }
+
+    /// Fallible version of `clear_session`.
+    pub fn try_clear_session(&self) -> MBResult<()> {
+        self.webview
+            .try_perform_cookie_command(CookieCommand::ClearSessionCookies)
+    }
+}
@@ -0,0 +1,113 @@
+use crate::error::MBResult;
+use crate::events::{DocumentReady, Navigation, Subscription};
+use crate::mb_call;
+use crate::query::json_quote;
+use crate::types::{EmitPayload, WebFrameHandle};
+use crate::webview::WebView;
+
+/// The emissions waiting for the document, and the listeners tracking its state.
+#[derive(Default)]
+pub(crate) struct EmitQueue {
+    ready: bool,
+    pending: Vec<(Option<WebFrameHandle>, String)>,
+    listeners: Vec<Subscription>,
+}
+
+// The frame handles are only used by the listeners, which run on the UI thread.
+unsafe impl Send for EmitQueue {}
+
+/// The script dispatching `event` on the window of the frame, with `detail` as a JavaScript expression.
+pub(crate) fn script(event: &str, detail: &str) -> String {
+    format!(
+        "window.dispatchEvent(new CustomEvent({}, {{ detail: {} }}));",
+        json_quote(event),
+        detail
+    )
+}
+
+/// Dispatch the event in `frame`, or in the main frame if `None`.
+///
+/// While the page is loading and its document is not ready, the event is queued until the
+/// main frame emits the DOM ready event.
+pub(crate) fn emit<P>(
+    webview: &WebView,
+    frame: Option<WebFrameHandle>,
+    event: &str,
+    payload: &P,
+) -> MBResult<()>
+where
+    P: EmitPayload + ?Sized,
+{
+    let script = script(event, &payload.to_js()?);
+    install(webview)?;
+
+    let ready = webview.inner.emits.lock().unwrap().ready;
+    if !ready {
+        let loading = unsafe { mb_call!(mbIsLoading(webview.try_id()?)) }? != 0;
+        let mut queue = webview.inner.emits.lock().unwrap();
+        match loading {
+            true => {
+                queue.pending.push((frame, script));
+                return Ok(());
+            }
+            // Nothing is loading, for example the navigation was canceled.
+            false => queue.ready = true,
+        }
+    }
+    run(webview, frame, &script)
+}
+
+fn install(webview: &WebView) -> MBResult<()> {
+    if !webview.inner.emits.lock().unwrap().listeners.is_empty() {
+        return Ok(());
+    }
+
+    // The document is reset in `navigation_allowed`, once every listener allowed the navigation.
+    // This listener keeps the native callback installed.
+    let navigation = webview.try_subscribe::<Navigation, _>(i32::MIN, |_, _| true)?;
+    // After the command prelude, so listeners may call `window.rust.invoke`.
+    let document_ready =
+        match webview.try_subscribe::<DocumentReady, _>(i32::MAX - 2, |webview, frame| {
+            if matches!(
+                unsafe { mb_call!(mbIsMainFrame(webview.as_id(), frame.as_ptr())) },
+                Ok(0)
+            ) {
+                return;
+            }
+            flush(webview);
+        }) {
+            Ok(subscription) => subscription,
+            Err(error) => {
+                navigation.unsubscribe();
+                return Err(error);
+            }
+        };
+    webview.inner.emits.lock().unwrap().listeners = vec![navigation, document_ready];
+    Ok(())
+}
+
+/// Called by the navigation event once no listener canceled the navigation.
+///
+/// Emissions are queued again until the new document is ready.
+pub(crate) fn navigation_allowed(webview: &WebView) {
+    webview.inner.emits.lock().unwrap().ready = false;
+}
+
+fn flush(webview: &WebView) {
+    let pending = {
+        let mut queue = webview.inner.emits.lock().unwrap();
+        queue.ready = true;
+        std::mem::take(&mut queue.pending)
+    };
+    for (frame, script) in pending {
+        let _ = run(webview, frame, &script);
+    }
+}
+
+fn run(webview: &WebView, frame: Option<WebFrameHandle>, script: &str) -> MBResult<()> {
+    let frame = match frame {
+        Some(frame) => frame,
+        None => webview.try_get_mainframe()?,
+    };
+    webview.try_eval(frame, script, false).map(|_| ())
+}
@@ -1,3 +1,5 @@
+use std::sync::atomic::Ordering;
+
 /// Convenient type alias of Result type for miniblink.
 pub type MBResult<T> = std::result::Result<T, MBError>;
 
@@ -11,23 +13,93 @@ pub enum MBError {
     LibraryUnloaded(String),
     /// Undefined enum transmute
     UndefinedEnumTransmute,
+    /// Raised when the init settings are invalid.
+    InvalidSettings(String),
+    /// Raised when a string contains an interior nul byte.
+    InvalidString,
+    /// Raised when the loaded library does not export the function.
+    Unsupported(&'static str),
+    /// Raised when the api is called from a thread other than the UI thread.
+    WrongThread,
+    /// Raised when miniblink failed to create the webview.
+    CreateWebViewFailed,
+    /// Raised when a js value can not be converted to the requested type.
+    JsTypeMismatch {
+        /// The requested type.
+        expected: &'static str,
+        /// The type of the js value.
+        found: &'static str,
+    },
+    /// Raised when a frame was released, see `types::Frame`.
+    StaleFrame,
+    /// Raised when the webview is destroyed, see `WebView::destroy`.
+    Destroyed,
+    /// Raised when a payload can not be sent to JavaScript.
+    InvalidPayload(String),
+    /// Raised when routing a negative query channel id, which are reserved for
+    /// `params::QUERY_ERROR`.
+    InvalidChannel(i32),
+    /// Raised when a pending operation is dropped before miniblink completes it.
+    Canceled,
+    /// Raised when a cookie jar line can not be parsed, or a cookie can not be written.
+    InvalidCookie(String),
+    /// Raised when deleting a directory used by a webview which is not destroyed.
+    InUse(std::path::PathBuf),
+    /// Raised when writing the result of an operation to disk failed.
+    Io(std::io::Error),
+    /// Raised when init settings are given but miniblink is already initialized.
+    AlreadyInitialized,
 }
 
-impl MBError {
-    pub(crate) fn to_string(&self) -> String {
+impl std::fmt::Display for MBError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         use MBError::*;
         match self {
-            NotInitialized => "The miniblink is not initialized".into(),
-            LibraryUnloaded(error) => format!("Failed to load miniblink! {error}"),
-            UndefinedEnumTransmute => format!("Undefined enum transmute!"),
+            NotInitialized => write!(f, "The miniblink is not initialized"),
+            LibraryUnloaded(error) => write!(f, "Failed to load miniblink! {error}"),
+            UndefinedEnumTransmute => write!(f, "Undefined enum transmute!"),
+            InvalidSettings(error) => write!(f, "Invalid init settings! {error}"),
+            InvalidString => write!(f, "The string contains an interior nul byte!"),
+            Unsupported(name) => write!(f, "The miniblink library does not export {name}!"),
+            WrongThread => write!(f, "The api must be called on the UI thread!"),
+            CreateWebViewFailed => write!(f, "Failed to create webview!"),
+            JsTypeMismatch { expected, found } => {
+                write!(f, "Expected a js {expected} but found {found}!")
+            }
+            StaleFrame => write!(f, "The frame was released!"),
+            Destroyed => write!(f, "The webview is destroyed!"),
+            InvalidPayload(error) => write!(f, "Invalid payload! {error}"),
+            InvalidChannel(id) => write!(f, "The query channel {id} is reserved!"),
+            Canceled => write!(f, "The operation is canceled!"),
+            InvalidCookie(line) => write!(f, "Invalid cookie! {line}"),
+            InUse(path) => write!(f, "The directory {} is in use!", path.display()),
+            Io(error) => write!(f, "I/O error! {error}"),
+            AlreadyInitialized => write!(f, "The miniblink is already initialized!"),
         }
     }
 }
 
-impl std::fmt::Display for MBError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(&self.to_string())
-    }
+impl std::error::Error for MBError {}
+
+/// Unwrap the result of a `try_*` method in its non-fallible version.
+pub(crate) trait UnwrapOrGone<T> {
+    /// Get the value, or the default value if `app::uninit` was called or the webview is
+    /// destroyed.
+    ///
+    /// # Panics
+    /// Panics on any other error.
+    fn unwrap_or_gone(self) -> T;
 }
 
-impl std::error::Error for MBError {}
+impl<T: Default> UnwrapOrGone<T> for MBResult<T> {
+    #[track_caller]
+    fn unwrap_or_gone(self) -> T {
+        match self {
+            Err(MBError::Destroyed) => T::default(),
+            Err(MBError::NotInitialized) if crate::UNINITIALIZED.load(Ordering::SeqCst) => {
+                T::default()
+            }
+            result => result.unwrap(),
+        }
+    }
+}
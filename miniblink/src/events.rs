@@ -0,0 +1,405 @@
+use std::any::TypeId;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Weak};
+
+use crate::callback::CallbackHandle;
+use crate::error::MBResult;
+use crate::net_job::NetJob;
+use crate::params::*;
+use crate::types::WebFrameHandle;
+use crate::webview::{WebView, WebViewInner};
+
+/// An event which any number of listeners can subscribe to. See `WebView::subscribe`.
+///
+/// One native callback is installed per event and webview, and it calls the listeners from the
+/// highest priority to the lowest. Listeners with the same priority are called in the order they
+/// subscribed.
+///
+/// # Remarks
+/// The matching `on_*` method of `WebView` adds a listener with priority 0, which its next call
+/// replaces. It does not affect the other listeners.
+///
+/// ```rust,no_run
+/// use miniblink::{app, events, webview::WebView};
+///
+/// app::init("./mb.dll").unwrap();
+/// let view = WebView::default();
+/// view.subscribe::<events::UrlChanged, _>(10, |_, params| println!("{}", params.url));
+/// ```
+pub trait Event: sealed::Install + Sized + 'static {
+    /// The argument passed to the listeners.
+    type Args: ?Sized;
+    /// The result returned by the listeners.
+    type Output;
+
+    /// The result if no listener returned one.
+    fn initial() -> Self::Output;
+
+    /// Combine the result of a listener with the results so far.
+    fn combine(acc: Self::Output, result: Self::Output) -> Self::Output;
+
+    /// The result of a listener which panicked.
+    fn panicked() -> Self::Output;
+}
+
+pub(crate) mod sealed {
+    use crate::callback::CallbackHandle;
+    use crate::error::MBResult;
+    use crate::webview::WebView;
+
+    pub trait Install {
+        fn install(webview: &WebView) -> MBResult<CallbackHandle>;
+    }
+}
+
+macro_rules! define_event {
+    (
+        $(
+            $(#[$attr:meta])*
+            $name:ident via $on:ident;
+        )*
+    ) => {
+        $(
+            $(#[$attr])*
+            #[derive(Debug, Clone, Copy)]
+            pub struct $name;
+
+            impl sealed::Install for $name {
+                fn install(webview: &WebView) -> MBResult<CallbackHandle> {
+                    webview.$on(|webview, args| dispatch::<$name>(webview, args))
+                }
+            }
+        )*
+    };
+}
+
+define_event! {
+    /// The URL changed. See `WebView::on_url_changed`.
+    UrlChanged via install_on_url_changed;
+    /// The document title changed. See `WebView::on_title_changed`.
+    TitleChanged via install_on_title_changed;
+    /// A frame emitted the DOM ready event. See `WebView::on_document_ready`.
+    DocumentReady via install_on_document_ready;
+    /// A frame finished loading, failed or is canceled. See `WebView::on_loading_finish`.
+    LoadingFinish via install_on_loading_finish;
+    /// The page wrote to the console. See `WebView::on_console`.
+    Console via install_on_console;
+    /// A script context was created. See `WebView::on_did_create_script_context`.
+    ScriptContextCreated via install_on_did_create_script_context;
+    /// A script context is about to be released. See `WebView::on_will_release_script_context`.
+    ScriptContextReleased via install_on_will_release_script_context;
+}
+
+macro_rules! impl_observer {
+    ($($name:ident: $args:ty),*) => {
+        $(
+            impl Event for $name {
+                type Args = $args;
+                type Output = ();
+
+                fn initial() {}
+
+                fn combine(_: (), _: ()) {}
+
+                fn panicked() {}
+            }
+        )*
+    };
+}
+
+impl_observer!(
+    UrlChanged: UrlChangedParameters,
+    TitleChanged: str,
+    DocumentReady: WebFrameHandle,
+    LoadingFinish: LoadingFinishParameters,
+    ScriptContextCreated: ScriptContextParameters,
+    ScriptContextReleased: ScriptContextParameters,
+    Console: ConsoleMessage,
+    LoadUrlHeadersReceived: LoadUrlHeadersReceivedParameters
+);
+
+macro_rules! impl_veto {
+    ($($name:ident: $args:ty),*) => {
+        $(
+            impl Event for $name {
+                type Args = $args;
+                type Output = bool;
+
+                fn initial() -> bool {
+                    true
+                }
+
+                fn combine(acc: bool, result: bool) -> bool {
+                    acc && result
+                }
+
+                fn panicked() -> bool {
+                    false
+                }
+            }
+        )*
+    };
+}
+
+impl_veto!(Navigation: NavigationParameters, Close: (), Destroy: ());
+
+/// A navigation is about to start. See `WebView::on_navigation`.
+///
+/// Listeners return true to continue. Any listener returning false or panicking cancels the
+/// navigation.
+///
+/// ```rust,no_run
+/// use miniblink::{app, events, webview::WebView};
+///
+/// app::init("./mb.dll").unwrap();
+/// let view = WebView::default();
+/// view.subscribe::<events::Navigation, _>(0, |_, params| params.url.starts_with("https://"));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Navigation;
+
+impl sealed::Install for Navigation {
+    fn install(webview: &WebView) -> MBResult<CallbackHandle> {
+        webview.install_on_navigation(|webview, args| {
+            let allowed = dispatch::<Navigation>(webview, args);
+            if allowed {
+                crate::load::navigation_allowed(webview, &args.url);
+                crate::emit::navigation_allowed(webview);
+            }
+            allowed
+        })
+    }
+}
+
+/// The window is requesting to close. See `WebView::on_close`.
+///
+/// Listeners return true to allow closing. Any listener returning false keeps the window open.
+#[derive(Debug, Clone, Copy)]
+pub struct Close;
+
+impl sealed::Install for Close {
+    fn install(webview: &WebView) -> MBResult<CallbackHandle> {
+        webview.install_on_close(|webview| dispatch::<Close>(webview, &()))
+    }
+}
+
+/// The window is being destroyed. See `WebView::on_destroy`.
+#[derive(Debug, Clone, Copy)]
+pub struct Destroy;
+
+impl sealed::Install for Destroy {
+    fn install(webview: &WebView) -> MBResult<CallbackHandle> {
+        webview.install_on_destroy(|webview| dispatch::<Destroy>(webview, &()))
+    }
+}
+
+/// The response headers of a network request are received. See
+/// `WebView::on_load_url_headers_received`.
+#[derive(Debug, Clone, Copy)]
+pub struct LoadUrlHeadersReceived;
+
+impl sealed::Install for LoadUrlHeadersReceived {
+    fn install(webview: &WebView) -> MBResult<CallbackHandle> {
+        webview.install_on_load_url_headers_received(|webview, url, job| {
+            let args = LoadUrlHeadersReceivedParameters {
+                url: url.to_string(),
+                job: NetJob { inner: job.inner },
+            };
+            dispatch::<LoadUrlHeadersReceived>(webview, &args)
+        })
+    }
+}
+
+type Listener<E> = Arc<dyn Fn(&WebView, &<E as Event>::Args) -> <E as Event>::Output + Send>;
+
+struct Entry<E: Event> {
+    id: u64,
+    priority: i32,
+    listener: Listener<E>,
+}
+
+/// The listeners of one event on one webview.
+pub(crate) struct Listeners<E: Event> {
+    entries: Vec<Entry<E>>,
+    handle: Option<CallbackHandle>,
+}
+
+// The listeners are only called by the shims, which run on the UI thread.
+unsafe impl<E: Event> Send for Listeners<E> {}
+
+static NEXT_LISTENER_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Add a listener to the webview, installing the native callback of the event if needed.
+pub(crate) fn subscribe<E, F>(
+    webview: &WebView,
+    priority: i32,
+    listener: F,
+) -> MBResult<Subscription>
+where
+    E: Event,
+    F: Fn(&WebView, &E::Args) -> E::Output + Send + 'static,
+{
+    let id = NEXT_LISTENER_ID.fetch_add(1, Ordering::Relaxed);
+    let install = {
+        let mut events = webview.inner.events.lock().unwrap();
+        let listeners = events
+            .entry(TypeId::of::<E>())
+            .or_insert_with(|| {
+                Box::new(Listeners::<E> {
+                    entries: Vec::new(),
+                    handle: None,
+                })
+            })
+            .downcast_mut::<Listeners<E>>()
+            .unwrap();
+        let index = listeners
+            .entries
+            .iter()
+            .position(|entry| entry.priority < priority)
+            .unwrap_or(listeners.entries.len());
+        listeners.entries.insert(
+            index,
+            Entry {
+                id,
+                priority,
+                listener: Arc::new(listener),
+            },
+        );
+        !listeners
+            .handle
+            .as_ref()
+            .is_some_and(CallbackHandle::is_registered)
+    };
+
+    let subscription = Subscription {
+        webview: Arc::downgrade(&webview.inner),
+        event: TypeId::of::<E>(),
+        id,
+        unsubscribe: unsubscribe::<E>,
+    };
+    if install {
+        match E::install(webview) {
+            Ok(handle) => {
+                let mut events = webview.inner.events.lock().unwrap();
+                if let Some(listeners) = events
+                    .get_mut(&TypeId::of::<E>())
+                    .and_then(|x| x.downcast_mut::<Listeners<E>>())
+                {
+                    listeners.handle = Some(handle);
+                }
+            }
+            Err(error) => {
+                unsubscribe::<E>(&webview.inner, id);
+                return Err(error);
+            }
+        }
+    }
+    Ok(subscription)
+}
+
+/// Set the listener of the `on_*` method of the event, replacing the one set before.
+///
+/// The listener has priority 0, and the other listeners keep running.
+pub(crate) fn set_direct<E, F>(webview: &WebView, listener: F) -> MBResult<CallbackHandle>
+where
+    E: Event,
+    F: Fn(&WebView, &E::Args) -> E::Output + Send + 'static,
+{
+    let subscription = subscribe::<E, F>(webview, 0, listener)?;
+    let key = std::any::type_name::<E>();
+    Ok(webview
+        .inner
+        .store_listener(key, subscription.id, unsubscribe::<E>))
+}
+
+/// Remove the listener, and the native callback once no listener is left.
+fn unsubscribe<E: Event>(inner: &WebViewInner, id: u64) {
+    let handle = {
+        let mut events = inner.events.lock().unwrap();
+        let Some(listeners) = events
+            .get_mut(&TypeId::of::<E>())
+            .and_then(|x| x.downcast_mut::<Listeners<E>>())
+        else {
+            return;
+        };
+        listeners.entries.retain(|entry| entry.id != id);
+        match listeners.entries.is_empty() {
+            true => listeners.handle.take(),
+            false => None,
+        }
+    };
+    if let Some(handle) = handle {
+        handle.revoke();
+    }
+}
+
+/// Call the listeners of the event and combine their results.
+///
+/// # Remarks
+/// Every listener is called even if an earlier one vetoed. A panicking listener counts as
+/// `Event::panicked`, so it vetoes.
+pub(crate) fn dispatch<E: Event>(webview: &WebView, args: &E::Args) -> E::Output {
+    let listeners: Vec<Listener<E>> = {
+        let events = webview.inner.events.lock().unwrap();
+        match events
+            .get(&TypeId::of::<E>())
+            .and_then(|x| x.downcast_ref::<Listeners<E>>())
+        {
+            Some(listeners) => listeners
+                .entries
+                .iter()
+                .map(|entry| entry.listener.clone())
+                .collect(),
+            None => Vec::new(),
+        }
+    };
+
+    listeners.iter().fold(E::initial(), |acc, listener| {
+        match catch_unwind(AssertUnwindSafe(|| listener(webview, args))) {
+            Ok(result) => E::combine(acc, result),
+            Err(_) => E::combine(acc, E::panicked()),
+        }
+    })
+}
+
+/// A listener added by `WebView::subscribe`.
+///
+/// Dropping the subscription keeps the listener. Call `unsubscribe` to remove it.
+pub struct Subscription {
+    webview: Weak<WebViewInner>,
+    event: TypeId,
+    id: u64,
+    unsubscribe: fn(&WebViewInner, u64),
+}
+
+impl Subscription {
+    /// Remove the listener. The native callback is removed with the last listener of the event.
+    pub fn unsubscribe(self) {
+        if let Some(inner) = self.webview.upgrade() {
+            (self.unsubscribe)(&inner, self.id);
+        }
+    }
+}
+
+/// A subscription which refers to no listener, returned by `WebView::subscribe` on a destroyed
+/// webview.
+impl Default for Subscription {
+    fn default() -> Self {
+        Self {
+            webview: Weak::new(),
+            event: TypeId::of::<()>(),
+            id: 0,
+            unsubscribe: |_, _| {},
+        }
+    }
+}
+
+impl std::fmt::Debug for Subscription {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Subscription")
+            .field("event", &self.event)
+            .field("id", &self.id)
+            .finish()
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use miniblink_sys::mbJsExecState;
+
+use crate::error::{MBError, MBResult};
+use crate::events::{ScriptContextCreated, ScriptContextReleased, Subscription};
+use crate::mb_call;
+use crate::params::ScriptContextParameters;
+use crate::types::{Frame, WebFrameHandle};
+use crate::webview::{WebView, WebViewInner};
+
+/// The frames of a webview whose main world script context is alive.
+#[derive(Default)]
+pub(crate) struct FrameTree {
+    frames: HashMap<WebFrameHandle, FrameEntry>,
+    listeners: Vec<Subscription>,
+}
+
+struct FrameEntry {
+    id: u64,
+    parent: Option<WebFrameHandle>,
+}
+
+static NEXT_FRAME_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Start tracking the frames of the webview.
+///
+/// The frames are added before and removed after the other listeners, which can look them up.
+pub(crate) fn track(webview: &WebView) -> MBResult<()> {
+    let created = webview.try_subscribe::<ScriptContextCreated, _>(i32::MAX, did_create)?;
+    let released = match webview.try_subscribe::<ScriptContextReleased, _>(i32::MIN, will_release) {
+        Ok(subscription) => subscription,
+        Err(error) => {
+            created.unsubscribe();
+            return Err(error);
+        }
+    };
+    webview.inner.frames.lock().unwrap().listeners = vec![created, released];
+    Ok(())
+}
+
+fn did_create(webview: &WebView, params: &ScriptContextParameters) {
+    if params.world_id != 0 {
+        return;
+    }
+    let parent = unsafe {
+        mb_call!(mbGetParentWebFrameHandle(
+            webview.as_id(),
+            params.frame.as_ptr()
+        ))
+    };
+    let parent = match parent {
+        Ok(parent) if !parent.is_null() => Some(WebFrameHandle { inner: parent }),
+        _ => None,
+    };
+    // A new document gets a new id, so frames of the previous document become stale.
+    let entry = FrameEntry {
+        id: NEXT_FRAME_ID.fetch_add(1, Ordering::Relaxed),
+        parent,
+    };
+    webview
+        .inner
+        .frames
+        .lock()
+        .unwrap()
+        .frames
+        .insert(params.frame, entry);
+}
+
+fn will_release(webview: &WebView, params: &ScriptContextParameters) {
+    if params.world_id != 0 {
+        return;
+    }
+    webview
+        .inner
+        .frames
+        .lock()
+        .unwrap()
+        .frames
+        .remove(&params.frame);
+    crate::query::unroute_frame(webview, params.frame);
+}
+
+/// Get the tracked frame of the handle.
+pub(crate) fn get(webview: &WebView, handle: WebFrameHandle) -> Option<Frame> {
+    let tree = webview.inner.frames.lock().unwrap();
+    let entry = tree.frames.get(&handle)?;
+    Some(Frame {
+        webview: Arc::downgrade(&webview.inner),
+        handle,
+        id: entry.id,
+    })
+}
+
+/// Get every tracked frame, or those whose parent is `parent`.
+pub(crate) fn list(
+    inner: &Arc<WebViewInner>,
+    parent: Option<Option<WebFrameHandle>>,
+) -> Vec<Frame> {
+    let tree = inner.frames.lock().unwrap();
+    let mut frames: Vec<Frame> = tree
+        .frames
+        .iter()
+        .filter(|(_, entry)| parent.is_none_or(|parent| entry.parent == parent))
+        .map(|(handle, entry)| Frame {
+            webview: Arc::downgrade(inner),
+            handle: *handle,
+            id: entry.id,
+        })
+        .collect();
+    // In creation order, so parents come before their children.
+    frames.sort_by_key(|frame| frame.id);
+    frames
+}
+
+/// Get the parent of the frame, checking it is not stale.
+pub(crate) fn parent(inner: &WebViewInner, frame: &Frame) -> MBResult<Option<WebFrameHandle>> {
+    let tree = inner.frames.lock().unwrap();
+    match tree.frames.get(&frame.handle) {
+        Some(entry) if entry.id == frame.id => Ok(entry.parent),
+        _ => Err(MBError::StaleFrame),
+    }
+}
+
+/// Find the tracked frame whose global exec state is `es`.
+pub(crate) fn find_by_exec(webview: &WebView, es: mbJsExecState) -> Option<WebFrameHandle> {
+    if es.is_null() {
+        return None;
+    }
+    let handles: Vec<WebFrameHandle> = webview
+        .inner
+        .frames
+        .lock()
+        .unwrap()
+        .frames
+        .keys()
+        .copied()
+        .collect();
+    handles.into_iter().find(|handle| {
+        matches!(
+            unsafe { mb_call!(mbGetGlobalExecByFrame(webview.as_id(), handle.as_ptr())) },
+            Ok(frame_es) if frame_es == es
+        )
+    })
+}
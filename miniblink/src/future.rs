@@ -0,0 +1,126 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+use crate::error::{MBError, MBResult};
+
+struct Shared<T> {
+    value: Option<MBResult<T>>,
+    waker: Option<Waker>,
+    abandoned: bool,
+    on_abandon: Option<Box<dyn FnOnce() + Send>>,
+}
+
+/// A value produced by a miniblink callback.
+///
+/// The future does not depend on any executor. It is woken from the UI thread when miniblink
+/// calls back, so the message loop must keep running while it is awaited. Blocking the UI thread
+/// on the future never completes it.
+///
+/// Resolves to `MBError::Canceled` if the callback is dropped before it is called, for example
+/// when the webview is destroyed.
+pub struct MBFuture<T> {
+    shared: Arc<Mutex<Shared<T>>>,
+}
+
+/// Completes the paired `MBFuture`.
+pub(crate) struct Completer<T> {
+    shared: Option<Arc<Mutex<Shared<T>>>>,
+}
+
+impl<T> MBFuture<T> {
+    /// Create a pending future and its completer.
+    pub(crate) fn pending() -> (Self, Completer<T>) {
+        let shared = Arc::new(Mutex::new(Shared {
+            value: None,
+            waker: None,
+            abandoned: false,
+            on_abandon: None,
+        }));
+        let completer = Completer {
+            shared: Some(shared.clone()),
+        };
+        (Self { shared }, completer)
+    }
+
+    /// Create a future which is already completed.
+    pub(crate) fn ready(value: MBResult<T>) -> Self {
+        let (future, completer) = Self::pending();
+        completer.complete(value);
+        future
+    }
+}
+
+impl<T> Future for MBFuture<T> {
+    type Output = MBResult<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut shared = self.shared.lock().unwrap();
+        match shared.value.take() {
+            Some(value) => Poll::Ready(value),
+            None => {
+                shared.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<T> Drop for MBFuture<T> {
+    fn drop(&mut self) {
+        let on_abandon = {
+            let mut shared = self.shared.lock().unwrap();
+            shared.abandoned = true;
+            shared.on_abandon.take()
+        };
+        if let Some(on_abandon) = on_abandon {
+            on_abandon();
+        }
+    }
+}
+
+impl<T> Completer<T> {
+    /// Complete the future and wake the task awaiting it.
+    pub(crate) fn complete(mut self, value: MBResult<T>) {
+        self.set(value);
+    }
+
+    /// Check if the future was dropped, so nobody waits for the value anymore.
+    pub(crate) fn is_abandoned(&self) -> bool {
+        self.shared
+            .as_ref()
+            .is_none_or(|shared| shared.lock().unwrap().abandoned)
+    }
+
+    /// Call `f` on the thread dropping the future if it is dropped before it completes.
+    pub(crate) fn on_abandon<F>(&self, f: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        if let Some(shared) = &self.shared {
+            shared.lock().unwrap().on_abandon = Some(Box::new(f));
+        }
+    }
+
+    fn set(&mut self, value: MBResult<T>) {
+        let Some(shared) = self.shared.take() else {
+            return;
+        };
+        let waker = {
+            let mut shared = shared.lock().unwrap();
+            shared.value = Some(value);
+            shared.on_abandon = None;
+            shared.waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> Drop for Completer<T> {
+    fn drop(&mut self) {
+        self.set(Err(MBError::Canceled));
+    }
+}
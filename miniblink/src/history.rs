@@ -0,0 +1,244 @@
+use std::collections::VecDeque;
+use std::ffi::c_void;
+use std::sync::{Arc, Weak};
+use std::time::SystemTime;
+
+use miniblink_sys::{mbWebView, BOOL};
+
+use crate::app::invoke_command_sync;
+use crate::error::{MBError, MBResult, UnwrapOrGone};
+use crate::events::{Subscription, TitleChanged, UrlChanged};
+use crate::future::{Completer, MBFuture};
+use crate::mb_call;
+use crate::webview::{WebView, WebViewInner};
+
+/// An entry of the visited list. See `History::entries`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryEntry {
+    /// The url.
+    pub url: String,
+    /// The document title, empty until the page sets it.
+    pub title: String,
+    /// When the url was visited.
+    pub visited_at: SystemTime,
+}
+
+/// The number of entries `History::entries` keeps.
+pub const MAX_ENTRIES: usize = 1000;
+
+/// The visited list of a webview, and the listeners feeding it.
+#[derive(Default)]
+pub(crate) struct VisitedList {
+    entries: VecDeque<HistoryEntry>,
+    listeners: Vec<Subscription>,
+}
+
+impl VisitedList {
+    fn push(&mut self, entry: HistoryEntry) {
+        if self.entries.len() == MAX_ENTRIES {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+}
+
+/// Start recording the visited urls of the webview.
+pub(crate) fn track(webview: &WebView) -> MBResult<()> {
+    let url_changed = webview.try_subscribe::<UrlChanged, _>(i32::MAX, |webview, params| {
+        webview.inner.history.lock().unwrap().push(HistoryEntry {
+            url: params.url.clone(),
+            title: String::new(),
+            visited_at: SystemTime::now(),
+        });
+    })?;
+    let title_changed = webview.try_subscribe::<TitleChanged, _>(i32::MAX, |webview, title| {
+        if let Some(entry) = webview.inner.history.lock().unwrap().entries.back_mut() {
+            entry.title = title.to_string();
+        }
+    });
+    let title_changed = match title_changed {
+        Ok(subscription) => subscription,
+        Err(error) => {
+            url_changed.unsubscribe();
+            return Err(error);
+        }
+    };
+    webview.inner.history.lock().unwrap().listeners = vec![url_changed, title_changed];
+    Ok(())
+}
+
+/// The navigation history of a webview. See `WebView::history`.
+///
+/// ```rust,no_run
+/// use miniblink::{app, webview::WebView};
+///
+/// app::init("./mb.dll").unwrap();
+/// let view = WebView::default();
+/// let history = view.history();
+/// for entry in history.entries() {
+///     println!("{} {}", entry.title, entry.url);
+/// }
+/// history.go_to_offset(-1);
+/// ```
+///
+/// # Remarks
+/// The history does not keep the webview alive. Once the webview is dropped, the fallible api
+/// returns `MBError::Destroyed` and `entries` returns nothing.
+#[derive(Clone)]
+pub struct History {
+    webview: Weak<WebViewInner>,
+}
+
+impl History {
+    pub(crate) fn new(webview: &WebView) -> Self {
+        Self {
+            webview: Arc::downgrade(&webview.inner),
+        }
+    }
+
+    /// Get the webview owning the history.
+    pub fn webview(&self) -> Option<WebView> {
+        self.webview.upgrade().map(|inner| WebView { inner })
+    }
+
+    fn try_id(&self) -> MBResult<mbWebView> {
+        self.webview().ok_or(MBError::Destroyed)?.try_id()
+    }
+
+    /// Get the index of the current entry in the back/forward list.
+    pub fn current_index(&self) -> i32 {
+        self.try_current_index().unwrap_or_gone()
+    }
+
+    /// Fallible version of `current_index`.
+    pub fn try_current_index(&self) -> MBResult<i32> {
+        unsafe { mb_call!(mbGetNavigateIndex(self.try_id()?)) }
+    }
+
+    /// Go to the entry at `index` in the back/forward list.
+    pub fn go_to_index(&self, index: i32) {
+        self.try_go_to_index(index).unwrap_or_gone()
+    }
+
+    /// Fallible version of `go_to_index`.
+    pub fn try_go_to_index(&self, index: i32) -> MBResult<()> {
+        unsafe { mb_call!(mbGoToIndex(self.try_id()?, index)) }
+    }
+
+    /// Go `offset` entries back, if negative, or forward in the back/forward list.
+    pub fn go_to_offset(&self, offset: i32) {
+        self.try_go_to_offset(offset).unwrap_or_gone()
+    }
+
+    /// Fallible version of `go_to_offset`.
+    pub fn try_go_to_offset(&self, offset: i32) -> MBResult<()> {
+        unsafe { mb_call!(mbGoToOffset(self.try_id()?, offset)) }
+    }
+
+    /// Navigate to the entry at `index` in the back/forward list, like `go_to_index`.
+    ///
+    /// # Remarks
+    /// Some versions of `mb.dll` export only one of `mbGoToIndex` and `mbNavigateAtIndex`.
+    pub fn navigate_at_index(&self, index: i32) {
+        self.try_navigate_at_index(index).unwrap_or_gone()
+    }
+
+    /// Fallible version of `navigate_at_index`.
+    pub fn try_navigate_at_index(&self, index: i32) -> MBResult<()> {
+        unsafe { mb_call!(mbNavigateAtIndex(self.try_id()?, index)) }
+    }
+
+    /// Check if there is an entry to go back to.
+    pub fn can_go_back(&self) -> MBFuture<bool> {
+        self.can_go("mbCanGoBack")
+    }
+
+    /// Check if there is an entry to go forward to.
+    pub fn can_go_forward(&self) -> MBFuture<bool> {
+        self.can_go("mbCanGoForward")
+    }
+
+    /// Check if there is an entry to go back to, blocking until the UI thread answers.
+    pub fn can_go_back_sync(&self) -> bool {
+        self.try_can_go_back_sync().unwrap_or_gone()
+    }
+
+    /// Fallible version of `can_go_back_sync`.
+    pub fn try_can_go_back_sync(&self) -> MBResult<bool> {
+        let id = self.try_id()?;
+        invoke_command_sync(move || {
+            unsafe { mb_call!(mbCanGoBackOrForward(id, 1)) }.map(|x| x != 0)
+        })?
+    }
+
+    /// Check if there is an entry to go forward to, blocking until the UI thread answers.
+    pub fn can_go_forward_sync(&self) -> bool {
+        self.try_can_go_forward_sync().unwrap_or_gone()
+    }
+
+    /// Fallible version of `can_go_forward_sync`.
+    pub fn try_can_go_forward_sync(&self) -> MBResult<bool> {
+        let id = self.try_id()?;
+        invoke_command_sync(move || {
+            unsafe { mb_call!(mbCanGoBackOrForward(id, 0)) }.map(|x| x != 0)
+        })?
+    }
+
+    /// Get the visited urls, oldest first.
+    ///
+    /// # Remarks
+    /// The list records every url change since the webview was created, including those by
+    /// `history.pushState`. It is kept by this crate, so it does not follow the back/forward list
+    /// of miniblink: going back or forward appends the url again instead of moving in the list.
+    ///
+    /// Only the last `MAX_ENTRIES` urls are kept, the oldest are dropped first.
+    pub fn entries(&self) -> Vec<HistoryEntry> {
+        let Some(inner) = self.webview.upgrade() else {
+            return Vec::new();
+        };
+        let history = inner.history.lock().unwrap();
+        history.entries.iter().cloned().collect()
+    }
+
+    /// Clear the visited urls. The back/forward list is kept.
+    pub fn clear_entries(&self) {
+        if let Some(inner) = self.webview.upgrade() {
+            inner.history.lock().unwrap().entries.clear();
+        }
+    }
+
+    fn can_go(&self, name: &'static str) -> MBFuture<bool> {
+        let (future, completer) = MBFuture::pending();
+        let id = match self.try_id() {
+            Ok(id) => id,
+            Err(error) => return MBFuture::ready(Err(error)),
+        };
+        let api = match crate::call_symbol(name) {
+            Ok(api) => api,
+            Err(error) => return MBFuture::ready(Err(error)),
+        };
+
+        extern "system" fn shim(_: mbWebView, param: *mut c_void, state: i32, result: BOOL) {
+            let completer = unsafe { Box::from_raw(param as *mut Completer<bool>) };
+            // A failed request means there is no entry.
+            completer.complete(Ok(
+                state == miniblink_sys::kMbAsynRequestStateOk && result != 0
+            ));
+        }
+
+        let param = Box::into_raw(Box::new(completer)) as *mut c_void;
+        match name {
+            "mbCanGoBack" => unsafe { api.mbCanGoBack(id, Some(shim), param) },
+            _ => unsafe { api.mbCanGoForward(id, Some(shim), param) },
+        }
+        future
+    }
+}
+
+impl std::fmt::Debug for History {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("History")
+            .field("webview", &self.webview.upgrade().map(|inner| inner.id))
+            .finish()
+    }
+}
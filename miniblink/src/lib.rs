@@ -3,34 +3,89 @@
 
 /// Wraps to global functions.
 pub mod app;
+/// Defines the backend.
+pub mod backend;
 /// Defines the content.
 pub mod callback;
+/// Defines the cookie manager.
+pub mod cookie;
 /// Defines the miniblink error types.
 pub mod error;
+/// Defines the event bus.
+pub mod events;
+/// Defines the futures.
+pub mod future;
+/// Defines the navigation history.
+pub mod history;
 /// Defines the net.
 pub mod net_job;
 /// Defines the params.
 pub mod params;
+/// Defines the user data profiles.
+pub mod profile;
 /// Defines the types.
 pub mod types;
 /// Wraps to mbWebView.
 pub mod webview;
 
+pub(crate) mod command;
+pub(crate) mod emit;
+pub(crate) mod frame;
+pub(crate) mod load;
 pub(crate) mod mbstring;
+pub(crate) mod query;
 
+/// Call the backend function, returning `MBResult` instead of panicking if it is not available.
+/// Must be used inside `unsafe`. The arguments are evaluated in the calling function, so they may
+/// use `?`.
+macro_rules! mb_call {
+    ($name:ident($($arg:expr),* $(,)?)) => {
+        match $crate::call_symbol(stringify!($name)) {
+            Ok(api) => Ok(api.$name($($arg),*)),
+            Err(error) => Err(error),
+        }
+    };
+}
+
+pub(crate) use mb_call;
+
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::OnceLock;
 
+use backend::Backend;
 use error::{MBError, MBResult};
 
 type MbLibrary = miniblink_sys::Library;
 
-pub(crate) static LIB: OnceLock<MbLibrary> = OnceLock::new();
+pub(crate) static LIB: OnceLock<Box<dyn Backend>> = OnceLock::new();
+
+/// Set by `app::uninit`. The backend can not be used any more.
+pub(crate) static UNINITIALIZED: AtomicBool = AtomicBool::new(false);
 
 /// Call the inner api. Use it to call unwrapped api.
+///
+/// # Remarks
+/// Returns `MBError::NotInitialized` if miniblink is not backed by `miniblink_sys::Library`.
 pub fn call_api() -> MBResult<&'static MbLibrary> {
-    LIB.get().ok_or_else(|| MBError::NotInitialized)
+    call_backend()?.as_library().ok_or(MBError::NotInitialized)
+}
+
+/// Call the backend installed by `app::init` or `app::init_with_backend`.
+///
+/// # Remarks
+/// Returns `MBError::NotInitialized` after `app::uninit`.
+pub fn call_backend() -> MBResult<&'static dyn Backend> {
+    if UNINITIALIZED.load(Ordering::SeqCst) {
+        return Err(MBError::NotInitialized);
+    }
+    LIB.get().map(|x| x.as_ref()).ok_or(MBError::NotInitialized)
 }
 
-pub(crate) fn call_api_or_panic() -> &'static MbLibrary {
-    call_api().unwrap()
+/// Call the backend, checking it provides the `mb*` function `name`.
+pub(crate) fn call_symbol(name: &'static str) -> MBResult<&'static dyn Backend> {
+    let backend = call_backend()?;
+    match backend.has_symbol(name) {
+        true => Ok(backend),
+        false => Err(MBError::Unsupported(name)),
+    }
 }
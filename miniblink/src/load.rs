@@ -0,0 +1,187 @@
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::Arc;
+
+use crate::error::MBResult;
+use crate::events::{
+    DocumentReady, LoadUrlHeadersReceived, LoadingFinish, Navigation, Subscription,
+};
+use crate::mb_call;
+use crate::params::LoadFailure;
+use crate::types::{LoadFailureKind, LoadState, LoadingResult, WebFrameHandle};
+use crate::webview::WebView;
+
+pub(crate) type ErrorPageHandler = Arc<dyn Fn(&WebView, &LoadFailure) -> Option<String> + Send>;
+
+/// The load state of the main frame, the listeners tracking it and the error page handler.
+#[derive(Default)]
+pub(crate) struct LoadTracker {
+    pub(crate) state: LoadState,
+    pub(crate) error_page: Option<ErrorPageHandler>,
+    /// Set while the error page loads, which must not change the state.
+    rendering_error_page: bool,
+    /// Set from an allowed navigation until the response of the main frame is received.
+    awaiting_response: bool,
+    /// The status code and status line of the response of the main frame.
+    status: Option<(u16, String)>,
+    listeners: Vec<Subscription>,
+}
+
+// The handler is only called by the listeners, which run on the UI thread.
+unsafe impl Send for LoadTracker {}
+
+/// Start tracking the load state of the webview.
+pub(crate) fn track(webview: &WebView) -> MBResult<()> {
+    // The state changes in `navigation_allowed`, once every listener allowed the navigation.
+    // This listener keeps the native callback installed.
+    let navigation = webview.try_subscribe::<Navigation, _>(i32::MIN, |_, _| true)?;
+    let document_ready = webview.try_subscribe::<DocumentReady, _>(i32::MAX, |webview, frame| {
+        if is_main_frame(webview, *frame) && !rendering_error_page(webview) {
+            update(webview, |state| match state {
+                LoadState::Loading { url } => LoadState::DocumentReady { url },
+                state => state,
+            });
+        }
+    });
+    // The first response after the navigation is the main frame, before any of its resources.
+    let headers =
+        webview.try_subscribe::<LoadUrlHeadersReceived, _>(i32::MAX, |webview, params| {
+            if !webview.inner.load.lock().unwrap().awaiting_response {
+                return;
+            }
+            let status = params
+                .job
+                .try_get_raw_response_head()
+                .ok()
+                .and_then(|head| status_line(&head));
+            // A redirect is followed by the response of the new url.
+            if matches!(status, Some((300..=399, _))) {
+                return;
+            }
+            let mut tracker = webview.inner.load.lock().unwrap();
+            tracker.awaiting_response = false;
+            tracker.status = status;
+        });
+    let loading_finish = webview.try_subscribe::<LoadingFinish, _>(i32::MAX, |webview, params| {
+        if !is_main_frame(webview, params.frame_id) {
+            return;
+        }
+        // The error page finished, keep the failure so `retry_load` works.
+        if std::mem::take(&mut webview.inner.load.lock().unwrap().rendering_error_page) {
+            return;
+        }
+        let status = {
+            let mut tracker = webview.inner.load.lock().unwrap();
+            tracker.awaiting_response = false;
+            tracker.status.take()
+        };
+        let url = params.url.clone();
+        let failure = match (params.result, status) {
+            (LoadingResult::Failed, status) => Some(LoadFailure {
+                url: url.clone(),
+                kind: LoadFailureKind::from_reason(&params.failed_reason),
+                reason: params.failed_reason.clone(),
+                status: status.map(|(code, _)| code),
+            }),
+            (LoadingResult::Succeeded, Some((code, line))) if code >= 400 => Some(LoadFailure {
+                url: url.clone(),
+                kind: LoadFailureKind::Http,
+                reason: line,
+                status: Some(code),
+            }),
+            _ => None,
+        };
+        update(webview, |_| match (&failure, params.result) {
+            (Some(failure), _) => LoadState::Failed {
+                url,
+                reason: failure.reason.clone(),
+            },
+            (None, LoadingResult::Canceled) => LoadState::Canceled { url },
+            (None, _) => LoadState::Loaded { url },
+        });
+        if let Some(failure) = failure {
+            render_error_page(webview, failure);
+        }
+    });
+
+    let mut listeners = vec![navigation];
+    for subscription in [document_ready, headers, loading_finish] {
+        match subscription {
+            Ok(subscription) => listeners.push(subscription),
+            Err(error) => {
+                listeners.into_iter().for_each(Subscription::unsubscribe);
+                return Err(error);
+            }
+        }
+    }
+    webview.inner.load.lock().unwrap().listeners = listeners;
+    Ok(())
+}
+
+/// Called by the navigation event once no listener canceled the navigation.
+pub(crate) fn navigation_allowed(webview: &WebView, url: &str) {
+    if rendering_error_page(webview) {
+        return;
+    }
+    let mut tracker = webview.inner.load.lock().unwrap();
+    tracker.state = LoadState::Loading {
+        url: url.to_string(),
+    };
+    tracker.awaiting_response = true;
+    tracker.status = None;
+}
+
+fn update<F>(webview: &WebView, f: F)
+where
+    F: FnOnce(LoadState) -> LoadState,
+{
+    let mut tracker = webview.inner.load.lock().unwrap();
+    let state = std::mem::take(&mut tracker.state);
+    tracker.state = f(state);
+}
+
+fn rendering_error_page(webview: &WebView) -> bool {
+    webview.inner.load.lock().unwrap().rendering_error_page
+}
+
+/// Load the page returned by the error page handler in place of the failed url.
+///
+/// The page is loaded with the failed url as its base, so it takes the history entry which the
+/// failed load would have.
+fn render_error_page(webview: &WebView, failure: LoadFailure) {
+    let Some(handler) = webview.inner.load.lock().unwrap().error_page.clone() else {
+        return;
+    };
+    let html = catch_unwind(AssertUnwindSafe(|| handler(webview, &failure)));
+    let Ok(Some(html)) = html else {
+        return;
+    };
+
+    webview.inner.load.lock().unwrap().rendering_error_page = true;
+    if webview
+        .try_load_html_with_base_url(&html, &failure.url)
+        .is_err()
+    {
+        webview.inner.load.lock().unwrap().rendering_error_page = false;
+    }
+}
+
+/// The status code and status line of a raw response head, such as `HTTP/1.1 404 Not Found`.
+///
+/// # Remarks
+/// `mbNetGetHttpStatusCode` only takes the `mbWebUrlResponsePtr` of requests started with
+/// `mbNetStartUrlRequest`. Page loads only pass an `mbNetJob`, so the head is parsed instead.
+/// Heads without a status line, such as those of `file:` urls, give `None`.
+fn status_line(head: &[String]) -> Option<(u16, String)> {
+    head.iter().find_map(|line| {
+        let line = line.trim();
+        let code = line.strip_prefix("HTTP/")?.split_whitespace().nth(1)?;
+        Some((code.parse().ok()?, line.to_string()))
+    })
+}
+
+fn is_main_frame(webview: &WebView, frame: WebFrameHandle) -> bool {
+    matches!(
+        unsafe { mb_call!(mbIsMainFrame(webview.as_id(), frame.as_ptr())) },
+        Ok(x) if x != 0
+    )
+}
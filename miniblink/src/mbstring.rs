@@ -1,6 +1,46 @@
-use std::ffi::{CString, NulError};
+use std::ffi::{c_char, CStr, CString};
 
-use crate::call_api_or_panic;
+use widestring::U16CString;
+
+use crate::error::{MBError, MBResult};
+use crate::mb_call;
+
+/// Convert to a nul terminated string, returning `MBError::InvalidString` on interior nul bytes.
+pub(crate) fn to_cstring<T>(t: T) -> MBResult<CString>
+where
+    T: Into<Vec<u8>>,
+{
+    CString::new(t).map_err(|_| MBError::InvalidString)
+}
+
+/// Convert to a nul terminated wide string, returning `MBError::InvalidString` on interior nul bytes.
+pub(crate) fn to_wide_cstring(s: &str) -> MBResult<U16CString> {
+    U16CString::from_str(s).map_err(|_| MBError::InvalidString)
+}
+
+/// Copy a nul terminated utf8 string owned by miniblink. Null is an empty string.
+///
+/// # Safety
+/// The pointer must be null or point to a nul terminated string.
+pub(crate) unsafe fn from_utf8_ptr(ptr: *const c_char) -> String {
+    match ptr.is_null() {
+        true => String::new(),
+        false => CStr::from_ptr(ptr).to_string_lossy().to_string(),
+    }
+}
+
+/// Copy a utf8 buffer of `len` bytes owned by miniblink. Null is an empty string.
+///
+/// # Safety
+/// The pointer must be null or point to at least `len` bytes.
+pub(crate) unsafe fn from_utf8_parts(ptr: *const c_char, len: usize) -> String {
+    match ptr.is_null() {
+        true => String::new(),
+        false => {
+            String::from_utf8_lossy(std::slice::from_raw_parts(ptr as *const u8, len)).into_owned()
+        }
+    }
+}
 
 pub(crate) struct MbString {
     inner: *mut miniblink_sys::mbString,
@@ -8,21 +48,23 @@ pub(crate) struct MbString {
 
 #[allow(unused)]
 impl MbString {
-    pub(crate) fn new<T>(t: T) -> Result<Self, NulError>
+    pub(crate) fn new<T>(t: T) -> MBResult<Self>
     where
         T: Into<Vec<u8>>,
     {
-        let c_string = CString::new(t.into())?;
+        let c_string = to_cstring(t)?;
         let inner = unsafe {
-            call_api_or_panic().mbCreateString(c_string.as_ptr(), c_string.as_bytes().len() as _)
-        };
+            mb_call!(mbCreateString(
+                c_string.as_ptr(),
+                c_string.as_bytes().len() as _
+            ))
+        }?;
         Ok(Self { inner })
     }
 
-    pub(crate) unsafe fn from_vec_unchecked(vec: Vec<u8>) -> Self {
-        let inner =
-            unsafe { call_api_or_panic().mbCreateString(vec.as_ptr() as _, vec.len() as _) };
-        Self { inner }
+    pub(crate) unsafe fn from_vec_unchecked(vec: Vec<u8>) -> MBResult<Self> {
+        let inner = unsafe { mb_call!(mbCreateString(vec.as_ptr() as _, vec.len() as _)) }?;
+        Ok(Self { inner })
     }
 
     pub(crate) fn into_raw(self) -> *mut miniblink_sys::mbString {
@@ -30,12 +72,21 @@ impl MbString {
         s.inner
     }
 
-    /// Create a new `MbString` from a raw pointer from `into_raw`.
+    /// Take ownership of a string from `into_raw` or returned by miniblink.
     pub(crate) unsafe fn from_raw(inner: *mut miniblink_sys::mbString) -> Self {
-        assert!(inner.is_null());
+        assert!(!inner.is_null());
         Self { inner }
     }
 
+    /// Copy the content, replacing invalid utf8 sequences.
+    pub(crate) fn to_string_lossy(&self) -> MBResult<String> {
+        unsafe {
+            let ptr = mb_call!(mbGetString(self.inner))?;
+            let len = mb_call!(mbGetStringLen(self.inner))?;
+            Ok(from_utf8_parts(ptr, len))
+        }
+    }
+
     pub(crate) fn as_ptr(&self) -> *mut miniblink_sys::mbString {
         self.inner
     }
@@ -43,8 +94,8 @@ impl MbString {
 
 impl Drop for MbString {
     fn drop(&mut self) {
-        unsafe {
-            call_api_or_panic().mbDeleteString(self.inner);
+        if let Ok(api) = crate::call_backend() {
+            unsafe { api.mbDeleteString(self.inner) };
         }
     }
 }
@@ -1,9 +1,12 @@
-use std::ffi::{CStr, CString};
+use std::ffi::{c_void, CStr};
 
-use miniblink_sys::mbNetJob;
-use widestring::WideCString;
+use miniblink_sys::{mbNetJob, mbSlist};
 
-use crate::{call_api_or_panic, types::RequestType};
+use crate::app::invoke_command_sync;
+use crate::error::{MBError, MBResult, UnwrapOrGone};
+use crate::mb_call;
+use crate::mbstring::{to_cstring, to_wide_cstring};
+use crate::types::RequestType;
 
 /// Wraps to mbNetJob.
 ///
@@ -16,69 +19,296 @@ pub struct NetJob {
 impl NetJob {
     /// Set the http header field. Called in the on_load_url_begin callback.
     pub fn set_http_header_field(&self, name: &str, value: &str) {
-        let name = WideCString::from_str(name).unwrap();
-        let value = WideCString::from_str(value).unwrap();
+        self.try_set_http_header_field(name, value).unwrap_or_gone()
+    }
+
+    /// Fallible version of `set_http_header_field`.
+    pub fn try_set_http_header_field(&self, name: &str, value: &str) -> MBResult<()> {
+        let name = to_wide_cstring(name)?;
+        let value = to_wide_cstring(value)?;
         unsafe {
-            call_api_or_panic().mbNetSetHTTPHeaderField(
+            mb_call!(mbNetSetHTTPHeaderField(
                 self.inner,
                 name.as_ptr(),
                 value.as_ptr(),
                 0,
-            )
-        };
+            ))
+        }
     }
 
     /// Get the raw http head. Called in the on_load_url_begin callback.
     pub fn get_raw_http_head(&self) -> Vec<String> {
-        let mut item_ptr =
-            unsafe { call_api_or_panic().mbNetGetRawHttpHeadInBlinkThread(self.inner) };
-        let mut vec = Vec::new();
-        while item_ptr != std::ptr::null_mut() {
-            let item = unsafe { *item_ptr };
-            let head = unsafe { CStr::from_ptr(item.data).to_string_lossy().to_string() };
-            vec.push(head);
-            item_ptr = item.next;
-        }
-        vec
+        self.try_get_raw_http_head().unwrap_or_gone()
+    }
+
+    /// Fallible version of `get_raw_http_head`.
+    pub fn try_get_raw_http_head(&self) -> MBResult<Vec<String>> {
+        let list = unsafe { mb_call!(mbNetGetRawHttpHeadInBlinkThread(self.inner)) }?;
+        Ok(unsafe { slist_to_vec(list) })
+    }
+
+    /// Get the raw response head. Called in the on_load_url_headers_received callback.
+    pub fn get_raw_response_head(&self) -> Vec<String> {
+        self.try_get_raw_response_head().unwrap_or_gone()
+    }
+
+    /// Fallible version of `get_raw_response_head`.
+    pub fn try_get_raw_response_head(&self) -> MBResult<Vec<String>> {
+        let list = unsafe { mb_call!(mbNetGetRawResponseHeadInBlinkThread(self.inner)) }?;
+        Ok(unsafe { slist_to_vec(list) })
     }
 
     /// Set the mime type. Called in the on_load_url_begin callback.
     pub fn set_mime_type(&self, mime_type: &str) {
-        let mime_type = CString::new(mime_type).unwrap();
-        unsafe {
-            call_api_or_panic().mbNetSetMIMEType(self.inner, mime_type.as_ptr());
-        }
+        self.try_set_mime_type(mime_type).unwrap_or_gone()
+    }
+
+    /// Fallible version of `set_mime_type`.
+    pub fn try_set_mime_type(&self, mime_type: &str) -> MBResult<()> {
+        let mime_type = to_cstring(mime_type)?;
+        unsafe { mb_call!(mbNetSetMIMEType(self.inner, mime_type.as_ptr())) }
     }
 
     /// Get the mime type. Called in the on_load_url_begin callback.
     pub fn get_mime_type(&self) -> String {
-        let mime_type_ptr = unsafe { call_api_or_panic().mbNetGetMIMEType(self.inner) };
-        unsafe { CStr::from_ptr(mime_type_ptr).to_string_lossy().to_string() }
+        self.try_get_mime_type().unwrap_or_gone()
+    }
+
+    /// Fallible version of `get_mime_type`.
+    pub fn try_get_mime_type(&self) -> MBResult<String> {
+        let mime_type_ptr = unsafe { mb_call!(mbNetGetMIMEType(self.inner)) }?;
+        Ok(unsafe { CStr::from_ptr(mime_type_ptr).to_string_lossy().to_string() })
     }
 
     /// Get the request method.
     pub fn get_request_method(&self) -> RequestType {
-        let method_ptr = unsafe { call_api_or_panic().mbNetGetRequestMethod(self.inner) };
-        unsafe { std::mem::transmute(method_ptr) }
+        self.try_get_request_method().unwrap_or_gone()
+    }
+
+    /// Fallible version of `get_request_method`.
+    pub fn try_get_request_method(&self) -> MBResult<RequestType> {
+        let method = unsafe { mb_call!(mbNetGetRequestMethod(self.inner)) }?;
+        match method {
+            0..=3 => Ok(unsafe { std::mem::transmute::<i32, RequestType>(method) }),
+            _ => Err(MBError::UndefinedEnumTransmute),
+        }
     }
 
     /// Set the request data. Called in the on_load_url_end callback.
-    pub fn set_request_data<I>(&self, data: I) where I: Into<Vec<u8>> {
-        let mut data = data.into();
-        data.shrink_to_fit();
-        let mut data = std::mem::ManuallyDrop::new(data);
-        let data_len = data.len();
-        let data = data.as_mut_ptr();
-        
+    pub fn set_request_data<I>(&self, data: I)
+    where
+        I: Into<Vec<u8>>,
+    {
+        self.try_set_request_data(data).unwrap_or_gone()
+    }
+
+    /// Fallible version of `set_request_data`.
+    pub fn try_set_request_data<I>(&self, data: I) -> MBResult<()>
+    where
+        I: Into<Vec<u8>>,
+    {
+        let api = crate::call_symbol("mbNetSetData")?;
+        let (data, data_len) = leak_data(data.into());
         unsafe {
-            call_api_or_panic().mbNetSetData(self.inner, data as _, data_len as i32);
+            api.mbNetSetData(self.inner, data, data_len);
         }
+        Ok(())
     }
 
     /// Hook the request. The on_load_url_begin callback will be called if and only if the request is hooked.
     pub fn hook_request(&self) {
-        unsafe {
-            call_api_or_panic().mbNetHookRequest(self.inner);
+        self.try_hook_request().unwrap_or_gone()
+    }
+
+    /// Fallible version of `hook_request`.
+    pub fn try_hook_request(&self) -> MBResult<()> {
+        unsafe { mb_call!(mbNetHookRequest(self.inner)) }
+    }
+
+    /// Hold the request to answer it later, maybe from another thread. Called in the
+    /// on_load_url_begin callback, which should then return true.
+    ///
+    /// ```rust,no_run
+    /// use miniblink::{app, webview::WebView};
+    ///
+    /// app::init("./mb.dll").unwrap();
+    /// let view = WebView::default();
+    /// view.on_load_url_begin(|_, url, job| {
+    ///     if !url.starts_with("https://api.example/") {
+    ///         return false;
+    ///     }
+    ///     let job = job.hold();
+    ///     // The answer is committed on the UI thread, so never wait for it here.
+    ///     std::thread::spawn(move || {
+    ///         job.respond(&[("Content-Type", "application/json")], r#"{"ok":true}"#);
+    ///     });
+    ///     true
+    /// });
+    /// ```
+    pub fn hold(&self) -> HeldJob {
+        self.try_hold().unwrap_or_gone()
+    }
+
+    /// Fallible version of `hold`.
+    pub fn try_hold(&self) -> MBResult<HeldJob> {
+        unsafe { mb_call!(mbNetHoldJobToAsynCommit(self.inner)) }?;
+        Ok(HeldJob {
+            inner: self.inner,
+            committed: false,
+        })
+    }
+}
+
+/// A request held by `NetJob::hold`, answered by one of its methods.
+///
+/// The methods may be called from any thread, they are run on the UI thread. Dropping the job
+/// without answering it sends the original request, like `resume`.
+///
+/// # Remarks
+/// If a `try_*` method fails, the job is not answered and dropping it tries `resume`. Once
+/// miniblink can not be reached, for example after `app::uninit`, the job is left held.
+pub struct HeldJob {
+    inner: mbNetJob,
+    committed: bool,
+}
+
+// The job is only touched on the UI thread, see `commit`.
+unsafe impl Send for HeldJob {}
+
+/// A job which refers to no request, returned by `NetJob::hold` once miniblink is uninitialized.
+impl Default for HeldJob {
+    fn default() -> Self {
+        Self {
+            inner: std::ptr::null_mut(),
+            committed: true,
         }
     }
-}
\ No newline at end of file
+}
+
+impl HeldJob {
+    /// Answer the request with the headers and body instead of sending it.
+    ///
+    /// # Remarks
+    /// Miniblink exports no function to set the status code, the page always sees `200`.
+    pub fn respond<B>(self, headers: &[(&str, &str)], body: B)
+    where
+        B: Into<Vec<u8>>,
+    {
+        self.try_respond(headers, body).unwrap_or_gone()
+    }
+
+    /// Fallible version of `respond`.
+    ///
+    /// # Remarks
+    /// On error the job is not answered, and may be left held, see `HeldJob`.
+    pub fn try_respond<B>(self, headers: &[(&str, &str)], body: B) -> MBResult<()>
+    where
+        B: Into<Vec<u8>>,
+    {
+        let mut mime_type = None;
+        let mut fields = Vec::new();
+        for (name, value) in headers {
+            if name.eq_ignore_ascii_case("content-type") {
+                mime_type = Some(to_cstring(*value)?);
+            }
+            fields.push((to_wide_cstring(name)?, to_wide_cstring(value)?));
+        }
+        let body = body.into();
+
+        self.commit(move |job| unsafe {
+            for (name, value) in &fields {
+                mb_call!(mbNetSetHTTPHeaderField(
+                    job,
+                    name.as_ptr(),
+                    value.as_ptr(),
+                    1
+                ))?;
+            }
+            if let Some(mime_type) = &mime_type {
+                mb_call!(mbNetSetMIMEType(job, mime_type.as_ptr()))?;
+            }
+            let (body, body_len) = leak_data(body);
+            mb_call!(mbNetSetData(job, body, body_len))?;
+            mb_call!(mbNetContinueJob(job))
+        })
+    }
+
+    /// Send the request to `url` instead.
+    pub fn redirect(self, url: &str) {
+        self.try_redirect(url).unwrap_or_gone()
+    }
+
+    /// Fallible version of `redirect`.
+    ///
+    /// # Remarks
+    /// On error the job is not answered, and may be left held, see `HeldJob`.
+    pub fn try_redirect(self, url: &str) -> MBResult<()> {
+        let url = to_cstring(url)?;
+        self.commit(move |job| unsafe {
+            mb_call!(mbNetChangeRequestUrl(job, url.as_ptr()))?;
+            mb_call!(mbNetContinueJob(job))
+        })
+    }
+
+    /// Cancel the request, the page sees a network error.
+    pub fn fail(self) {
+        self.try_fail().unwrap_or_gone()
+    }
+
+    /// Fallible version of `fail`.
+    ///
+    /// # Remarks
+    /// On error the job is not answered, and may be left held, see `HeldJob`.
+    pub fn try_fail(self) -> MBResult<()> {
+        self.commit(|job| unsafe { mb_call!(mbNetCancelRequest(job)) })
+    }
+
+    /// Send the original request.
+    pub fn resume(self) {
+        self.try_resume().unwrap_or_gone()
+    }
+
+    /// Fallible version of `resume`.
+    ///
+    /// # Remarks
+    /// On error the job is not answered, and may be left held, see `HeldJob`.
+    pub fn try_resume(self) -> MBResult<()> {
+        self.commit(|job| unsafe { mb_call!(mbNetContinueJob(job)) })
+    }
+
+    fn commit<F>(mut self, f: F) -> MBResult<()>
+    where
+        F: FnOnce(mbNetJob) -> MBResult<()> + Send + 'static,
+    {
+        let job = self.inner as usize;
+        invoke_command_sync(move || f(job as mbNetJob))??;
+        self.committed = true;
+        Ok(())
+    }
+}
+
+impl Drop for HeldJob {
+    fn drop(&mut self) {
+        if !self.committed {
+            let job = self.inner as usize;
+            let _ = invoke_command_sync(move || unsafe { mb_call!(mbNetContinueJob(job as _)) });
+        }
+    }
+}
+
+/// Leak `data` for `mbNetSetData`, which may read the buffer after it returns.
+fn leak_data(data: Vec<u8>) -> (*mut c_void, i32) {
+    let data = Box::leak(data.into_boxed_slice());
+    (data.as_mut_ptr() as _, data.len() as _)
+}
+
+/// Collect the strings of a list owned by miniblink.
+unsafe fn slist_to_vec(mut item_ptr: *const mbSlist) -> Vec<String> {
+    let mut vec = Vec::new();
+    while !item_ptr.is_null() {
+        let item = *item_ptr;
+        vec.push(CStr::from_ptr(item.data).to_string_lossy().to_string());
+        item_ptr = item.next;
+    }
+    vec
+}
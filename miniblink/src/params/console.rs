@@ -0,0 +1,15 @@
+use crate::types::ConsoleLevel;
+
+/// Parameters in console callback.
+pub struct ConsoleMessage {
+    /// The level.
+    pub level: ConsoleLevel,
+    /// The message.
+    pub message: String,
+    /// The url of the script which logged the message.
+    pub source: String,
+    /// The line in the script.
+    pub line: u32,
+    /// The stack trace, empty if not available.
+    pub stack_trace: String,
+}
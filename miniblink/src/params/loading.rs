@@ -0,0 +1,34 @@
+use crate::net_job::NetJob;
+use crate::types::{LoadFailureKind, LoadingResult, WebFrameHandle};
+
+/// Parameters in loading finish callback.
+pub struct LoadingFinishParameters {
+    /// The frame handle.
+    pub frame_id: WebFrameHandle,
+    /// The url.
+    pub url: String,
+    /// The loading result.
+    pub result: LoadingResult,
+    /// The reason if the loading failed.
+    pub failed_reason: String,
+}
+
+/// Parameters in load url headers received callback.
+pub struct LoadUrlHeadersReceivedParameters {
+    /// The url.
+    pub url: String,
+    /// The net job.
+    pub job: NetJob,
+}
+
+/// A failed load of the main frame. See `WebView::set_error_page_handler`.
+pub struct LoadFailure {
+    /// The url.
+    pub url: String,
+    /// The kind of failure.
+    pub kind: LoadFailureKind,
+    /// The reason reported by miniblink, or the status line of an HTTP error.
+    pub reason: String,
+    /// The HTTP status code, if the response was received with an HTTP status line.
+    pub status: Option<u16>,
+}
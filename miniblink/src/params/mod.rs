@@ -1,15 +1,19 @@
-pub(crate) mod cookie;
+pub(crate) mod console;
 pub(crate) mod download;
+pub(crate) mod loading;
 pub(crate) mod navigation;
 pub(crate) mod prompt;
 pub(crate) mod query;
+pub(crate) mod script;
 pub(crate) mod url;
 pub(crate) mod view;
 
-pub use cookie::*;
+pub use console::*;
 pub use download::*;
+pub use loading::*;
 pub use navigation::*;
 pub use prompt::*;
 pub use query::*;
+pub use script::*;
 pub use url::*;
 pub use view::*;
@@ -1,11 +1,24 @@
+use crate::types::WebFrameHandle;
+
 /// The js query parameters.
 pub struct JsQueryParameters {
     /// The custom message.
     pub custom_message: i32,
     /// The request.
     pub request: String,
+    /// The frame which sent the query, if it is tracked. See `types::Frame`.
+    pub frame: Option<WebFrameHandle>,
 }
 
+/// The `custom_message` of the response if a query routed by `WebView::route_query` failed.
+///
+/// The response is then `{"message": "..."}`. Negative channel ids are reserved, and routing one
+/// returns `MBError::InvalidChannel`.
+pub const QUERY_ERROR: i32 = -1;
+
+/// The `custom_message` reserved for the commands registered by `WebView::command`.
+pub const COMMAND_QUERY: i32 = 0x5255_5354;
+
 /// The js query result.
 pub struct JsQueryResult {
     /// The custom message.
@@ -13,4 +26,3 @@ pub struct JsQueryResult {
     /// The response.
     pub response: String,
 }
-
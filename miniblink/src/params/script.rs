@@ -0,0 +1,11 @@
+use crate::types::WebFrameHandle;
+
+/// Parameters in script context callbacks.
+pub struct ScriptContextParameters {
+    /// The frame owning the context.
+    pub frame: WebFrameHandle,
+    /// The extension group. Always zero when the context is released.
+    pub extension_group: i32,
+    /// The world id. Zero is the main world, which runs the page scripts.
+    pub world_id: i32,
+}
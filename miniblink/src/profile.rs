@@ -0,0 +1,193 @@
+use std::path::{Path, PathBuf};
+
+use crate::error::{MBError, MBResult};
+use crate::types::CacheSettings;
+use crate::webview::WebView;
+
+const COOKIE_JAR: &str = "cookies.dat";
+const LOCAL_STORAGE: &str = "LocalStorage";
+const CACHE: &str = "Cache";
+
+/// A user data directory holding the cookies, local storage and disk cache of webviews.
+///
+/// The directory has a fixed layout:
+///
+/// ```text
+/// <root>/cookies.dat
+/// <root>/LocalStorage/
+/// <root>/Cache/
+/// ```
+///
+/// Apply it with `WebView::set_profile`. Webviews sharing a profile share its storage.
+///
+/// ```rust,no_run
+/// use miniblink::{app, profile::Profile, types::CacheSettings, webview::WebView};
+///
+/// app::init("./mb.dll").unwrap();
+/// let profile = Profile::new("./profiles/account").cache(CacheSettings::new().disk_limit(64 << 20));
+/// let view = WebView::default();
+/// view.set_profile(&profile);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Profile {
+    root: Option<PathBuf>,
+    cache: CacheSettings,
+}
+
+impl Profile {
+    /// Create a profile stored in the directory `root`. The directory is created when applied.
+    pub fn new<P>(root: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        Self {
+            root: Some(root.into()),
+            cache: CacheSettings::default(),
+        }
+    }
+
+    /// Create a profile which persists nothing.
+    ///
+    /// # Remarks
+    /// Miniblink always writes cookies and local storage to disk, so each webview gets its own
+    /// temporary directory, removed when the webview is destroyed. The disk cache is disabled.
+    ///
+    /// ```rust,no_run
+    /// use miniblink::{app, profile::Profile, webview::WebView};
+    ///
+    /// app::init("./mb.dll").unwrap();
+    /// let view = WebView::default();
+    /// view.set_profile(&Profile::incognito());
+    /// ```
+    pub fn incognito() -> Self {
+        Self {
+            root: None,
+            cache: CacheSettings::default(),
+        }
+    }
+
+    /// Set the cache limits and level. The `path` of the settings is replaced by `cache_path`.
+    pub fn cache(mut self, settings: CacheSettings) -> Self {
+        self.cache = settings;
+        self
+    }
+
+    /// Check if the profile persists nothing.
+    pub fn is_incognito(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// Get the user data directory, `None` if incognito.
+    pub fn root(&self) -> Option<&Path> {
+        self.root.as_deref()
+    }
+
+    /// Get the cookie jar file, `None` if incognito.
+    pub fn cookie_jar_path(&self) -> Option<PathBuf> {
+        self.root.as_ref().map(|x| x.join(COOKIE_JAR))
+    }
+
+    /// Get the local storage directory, `None` if incognito.
+    pub fn local_storage_path(&self) -> Option<PathBuf> {
+        self.root.as_ref().map(|x| x.join(LOCAL_STORAGE))
+    }
+
+    /// Get the disk cache directory, `None` if incognito.
+    pub fn cache_path(&self) -> Option<PathBuf> {
+        self.root.as_ref().map(|x| x.join(CACHE))
+    }
+
+    /// Delete the user data directory. Does nothing if incognito.
+    ///
+    /// # Remarks
+    /// Returns `MBError::InUse` if a webview which is not destroyed uses the profile, or caches to
+    /// a directory inside it.
+    pub fn wipe(&self) -> MBResult<()> {
+        let Some(root) = &self.root else {
+            return Ok(());
+        };
+        remove_unused_dir(root, |webview| {
+            let storage = webview.inner.storage.lock().unwrap();
+            let cache = webview.inner.disk_cache.lock().unwrap();
+            storage
+                .iter()
+                .map(|x| x.root.clone())
+                .chain(cache.clone())
+                .collect()
+        })
+    }
+
+    /// Create the directories and point the storage of the webview at them.
+    pub(crate) fn apply(&self, webview: &WebView) -> MBResult<()> {
+        let root = match &self.root {
+            Some(root) => root.clone(),
+            None => std::env::temp_dir().join(format!(
+                "miniblink-incognito-{}-{}",
+                std::process::id(),
+                webview.as_id()
+            )),
+        };
+        let (local_storage, cache) = (root.join(LOCAL_STORAGE), root.join(CACHE));
+        for dir in [&local_storage, &cache] {
+            std::fs::create_dir_all(dir).map_err(MBError::Io)?;
+        }
+
+        let previous = webview.inner.storage.lock().unwrap().replace(Storage {
+            root: root.clone(),
+            temporary: self.is_incognito(),
+        });
+        if let Some(previous) = previous.filter(|x| x.root != root) {
+            previous.remove_temporary();
+        }
+
+        webview.try_set_cookie_jar_full_path(path_str(&root.join(COOKIE_JAR))?)?;
+        webview.try_set_local_storage_full_path(path_str(&local_storage)?)?;
+        webview.try_enable_disk_cache(!self.is_incognito())?;
+        webview.try_configure_cache(&CacheSettings {
+            path: (!self.is_incognito()).then_some(cache),
+            ..self.cache.clone()
+        })
+    }
+}
+
+/// The user data directory a webview uses, see `Profile`.
+pub(crate) struct Storage {
+    pub(crate) root: PathBuf,
+    temporary: bool,
+}
+
+impl Storage {
+    /// Delete the directory of an incognito profile, ignoring errors.
+    pub(crate) fn remove_temporary(&self) {
+        if self.temporary {
+            let _ = std::fs::remove_dir_all(&self.root);
+        }
+    }
+}
+
+/// Delete the directory, unless a webview which is not destroyed uses it or a directory inside it.
+///
+/// `used` lists the directories of a webview. Both sides are canonicalized, so different spellings
+/// of the same directory match. A missing directory is not an error.
+pub(crate) fn remove_unused_dir<F>(path: &Path, used: F) -> MBResult<()>
+where
+    F: Fn(&WebView) -> Vec<PathBuf>,
+{
+    let path_canonical = match path.canonicalize() {
+        Ok(path) => path,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(error) => return Err(MBError::Io(error)),
+    };
+    let in_use = crate::webview::live_webviews()
+        .iter()
+        .flat_map(used)
+        .any(|x| x.canonicalize().unwrap_or(x).starts_with(&path_canonical));
+    if in_use {
+        return Err(MBError::InUse(path.to_path_buf()));
+    }
+    std::fs::remove_dir_all(&path_canonical).map_err(MBError::Io)
+}
+
+fn path_str(path: &Path) -> MBResult<&str> {
+    path.to_str().ok_or(MBError::InvalidString)
+}
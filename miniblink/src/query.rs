@@ -0,0 +1,151 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::callback::CallbackHandle;
+use crate::error::{MBError, MBResult};
+use crate::params::{JsQueryParameters, JsQueryResult, QUERY_ERROR};
+use crate::types::WebFrameHandle;
+use crate::webview::WebView;
+
+type QueryHandler = Arc<dyn Fn(&WebView, &str) -> Result<String, String> + Send>;
+
+/// Routes `window.mbQuery` calls to a handler per `custom_message`, and optionally per frame.
+#[derive(Default)]
+pub(crate) struct QueryRouter {
+    handlers: HashMap<(i32, Option<WebFrameHandle>), QueryHandler>,
+    handle: Option<CallbackHandle>,
+}
+
+// The handlers are only called by the shim, which runs on the UI thread.
+unsafe impl Send for QueryRouter {}
+
+/// Set the handler of `channel_id` in `frame`, or in every frame if `None`, installing the
+/// `on_query` callback if needed. Negative channel ids are reserved for `QUERY_ERROR`.
+pub(crate) fn route<F>(
+    webview: &WebView,
+    channel_id: i32,
+    frame: Option<WebFrameHandle>,
+    handler: F,
+) -> MBResult<()>
+where
+    F: Fn(&WebView, &str) -> Result<String, String> + Send + 'static,
+{
+    if channel_id < 0 {
+        return Err(MBError::InvalidChannel(channel_id));
+    }
+    let install = {
+        let mut router = webview.inner.queries.lock().unwrap();
+        router
+            .handlers
+            .insert((channel_id, frame), Arc::new(handler));
+        !router
+            .handle
+            .as_ref()
+            .is_some_and(CallbackHandle::is_registered)
+    };
+
+    if install {
+        match webview.try_on_query(dispatch) {
+            Ok(handle) => webview.inner.queries.lock().unwrap().handle = Some(handle),
+            Err(error) => {
+                unroute(webview, channel_id, frame);
+                return Err(error);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Remove the handler of `channel_id` in `frame`, and the `on_query` callback once no handler is
+/// left.
+pub(crate) fn unroute(webview: &WebView, channel_id: i32, frame: Option<WebFrameHandle>) {
+    unroute_where(webview, |key| *key == (channel_id, frame));
+}
+
+/// Remove the handlers of the frame, whose handle may be reused by a later frame.
+pub(crate) fn unroute_frame(webview: &WebView, frame: WebFrameHandle) {
+    unroute_where(webview, |(_, x)| *x == Some(frame));
+}
+
+fn unroute_where<P>(webview: &WebView, predicate: P)
+where
+    P: Fn(&(i32, Option<WebFrameHandle>)) -> bool,
+{
+    let handle = {
+        let mut router = webview.inner.queries.lock().unwrap();
+        router.handlers.retain(|key, _| !predicate(key));
+        match router.handlers.is_empty() {
+            true => router.handle.take(),
+            false => None,
+        }
+    };
+    if let Some(handle) = handle {
+        handle.revoke();
+    }
+}
+
+fn dispatch(webview: &WebView, params: &JsQueryParameters) -> JsQueryResult {
+    let handler = {
+        let router = webview.inner.queries.lock().unwrap();
+        params
+            .frame
+            .and_then(|frame| router.handlers.get(&(params.custom_message, Some(frame))))
+            .or_else(|| router.handlers.get(&(params.custom_message, None)))
+            .cloned()
+    };
+    let result = match handler {
+        Some(handler) => handler(webview, &params.request),
+        None => Err(format!("no handler for query {}", params.custom_message)),
+    };
+    match result {
+        Ok(response) => JsQueryResult {
+            custom_message: params.custom_message,
+            response,
+        },
+        Err(message) => JsQueryResult {
+            custom_message: QUERY_ERROR,
+            response: format!("{{\"message\":{}}}", json_quote(&message)),
+        },
+    }
+}
+
+/// Quote the string as a JSON string literal, which is also a valid JavaScript string literal.
+pub(crate) fn json_quote(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            // Line separators end a JavaScript string literal in older engines.
+            '\u{2028}' => quoted.push_str("\\u2028"),
+            '\u{2029}' => quoted.push_str("\\u2029"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Wrap a typed handler into a query handler which speaks JSON.
+#[cfg(feature = "serde")]
+pub(crate) fn json_handler<Req, Resp, E, F>(
+    handler: F,
+) -> impl Fn(&WebView, &str) -> Result<String, String> + Send + 'static
+where
+    Req: serde::de::DeserializeOwned,
+    Resp: serde::Serialize,
+    E: std::fmt::Display,
+    F: Fn(&WebView, Req) -> Result<Resp, E> + Send + 'static,
+{
+    move |webview, request| {
+        let request = serde_json::from_str::<Req>(request)
+            .map_err(|error| format!("invalid request: {error}"))?;
+        let response = handler(webview, request).map_err(|error| error.to_string())?;
+        serde_json::to_string(&response).map_err(|error| format!("invalid response: {error}"))
+    }
+}
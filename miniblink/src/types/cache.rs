@@ -0,0 +1,102 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::error::MBResult;
+
+/// The cache settings of a webview. See `WebView::configure_cache`.
+///
+/// Fields left as `None` keep the current setting.
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+///
+/// use miniblink::{app, types::CacheSettings, webview::WebView};
+///
+/// app::init("./mb.dll").unwrap();
+/// let settings = CacheSettings::new()
+///     .path("./cache")
+///     .disk_limit(256 << 20)
+///     .resource_gc_interval(Duration::from_secs(30));
+/// let view = WebView::default();
+/// view.configure_cache(&settings);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CacheSettings {
+    /// The disk cache directory, created when applied.
+    pub path: Option<PathBuf>,
+    /// The limit in bytes of the cache kept in memory, set with `mbSetDiskCacheLimit`.
+    ///
+    /// mb.h does not document it, only its name and that `mbSetDiskCacheLimitDisk` is the disk
+    /// limit next to it.
+    pub memory_limit: Option<usize>,
+    /// The limit in bytes of the cache kept on disk.
+    pub disk_limit: Option<usize>,
+    /// The disk cache level, passed to `mbSetDiskCacheLevel` as is. mb.h does not document the
+    /// levels.
+    pub level: Option<i32>,
+    /// Whether resources are cached in memory.
+    pub memory_cache: Option<bool>,
+    /// How often unused resources are freed, rounded down to seconds.
+    pub resource_gc_interval: Option<Duration>,
+}
+
+impl CacheSettings {
+    /// Create settings which change nothing.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the disk cache directory.
+    pub fn path<P>(mut self, path: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Set the limit in bytes of the cache kept in memory, see the `memory_limit` field.
+    pub fn memory_limit(mut self, limit: usize) -> Self {
+        self.memory_limit = Some(limit);
+        self
+    }
+
+    /// Set the limit in bytes of the cache kept on disk.
+    pub fn disk_limit(mut self, limit: usize) -> Self {
+        self.disk_limit = Some(limit);
+        self
+    }
+
+    /// Set the disk cache level, see the `level` field.
+    pub fn level(mut self, level: i32) -> Self {
+        self.level = Some(level);
+        self
+    }
+
+    /// Enable the memory cache.
+    pub fn memory_cache(mut self, enabled: bool) -> Self {
+        self.memory_cache = Some(enabled);
+        self
+    }
+
+    /// Set how often unused resources are freed.
+    pub fn resource_gc_interval(mut self, interval: Duration) -> Self {
+        self.resource_gc_interval = Some(interval);
+        self
+    }
+
+    /// Delete the disk cache directory. Does nothing if `path` is not set.
+    ///
+    /// # Remarks
+    /// Returns `MBError::InUse` if a webview which is not destroyed caches to the directory, or to
+    /// a directory inside it.
+    pub fn clear_disk_cache(&self) -> MBResult<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        crate::profile::remove_unused_dir(path, |webview| {
+            let cache = webview.inner.disk_cache.lock().unwrap();
+            cache.iter().cloned().collect()
+        })
+    }
+}
@@ -0,0 +1,50 @@
+use crate::backend::{Backend, SYMBOLS};
+use crate::error::{MBError, MBResult};
+
+/// The functions exported by the loaded library. See `app::capabilities`.
+///
+/// ```rust,no_run
+/// use miniblink::app;
+///
+/// app::init("./mb.dll").unwrap();
+/// let capabilities = app::capabilities().unwrap();
+/// if !capabilities.has("mbUtilPrintToPdf") {
+///     println!("print to pdf is not supported by this mb.dll");
+/// }
+/// ```
+#[derive(Clone, Copy)]
+pub struct Capabilities {
+    pub(crate) backend: &'static dyn Backend,
+}
+
+impl Capabilities {
+    /// Check if the library exports the `mb*` function `symbol`.
+    pub fn has(&self, symbol: &str) -> bool {
+        self.backend.has_symbol(symbol)
+    }
+
+    /// Returns `MBError::Unsupported` if the library does not export `symbol`.
+    pub fn require(&self, symbol: &'static str) -> MBResult<()> {
+        match self.has(symbol) {
+            true => Ok(()),
+            false => Err(MBError::Unsupported(symbol)),
+        }
+    }
+
+    /// Get the functions used by this crate which the library does not export.
+    pub fn missing(&self) -> Vec<&'static str> {
+        SYMBOLS
+            .iter()
+            .copied()
+            .filter(|symbol| !self.has(symbol))
+            .collect()
+    }
+}
+
+impl std::fmt::Debug for Capabilities {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Capabilities")
+            .field("missing", &self.missing())
+            .finish()
+    }
+}
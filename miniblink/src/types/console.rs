@@ -0,0 +1,30 @@
+/// The level of a console message.
+#[repr(i32)]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub enum ConsoleLevel {
+    /// `console.log`.
+    Log = 1,
+    /// `console.warn`.
+    Warning = 2,
+    /// `console.error` and uncaught exceptions.
+    Error = 3,
+    /// `console.debug`.
+    Debug = 4,
+    /// `console.info`.
+    Info = 5,
+    /// An error which was reported, then handled by a promise rejection handler.
+    RevokedError = 6,
+}
+
+impl ConsoleLevel {
+    pub(crate) fn from_mb(level: i32) -> Self {
+        match level {
+            2 => ConsoleLevel::Warning,
+            3 => ConsoleLevel::Error,
+            4 => ConsoleLevel::Debug,
+            5 => ConsoleLevel::Info,
+            6 => ConsoleLevel::RevokedError,
+            _ => ConsoleLevel::Log,
+        }
+    }
+}
@@ -1,3 +1,7 @@
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::error::{MBError, MBResult};
 
 #[repr(i32)]
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
@@ -12,3 +16,370 @@ pub enum CookieCommand {
     /// Reload cookies from file. Same as curl command: CURLOPT_COOKIELIST, "RELOAD".
     ReloadCookiesFromFile = 3,
 }
+
+/// A cookie in the curl cookie store.
+///
+/// A `domain` starting with a dot also matches the subdomains, otherwise the cookie is sent to
+/// that host only.
+///
+/// ```
+/// use miniblink::types::Cookie;
+///
+/// let line = "#HttpOnly_.example.com\tTRUE\t/\tTRUE\t0\tsid\tabc";
+/// let cookie = Cookie::parse_jar_line(line).unwrap();
+/// assert!(cookie.http_only && cookie.secure && cookie.expires.is_none());
+/// assert!(cookie.matches("https://www.example.com/app"));
+/// assert!(!cookie.matches("http://www.example.com/app"));
+/// assert_eq!(cookie.to_jar_line(), line);
+/// ```
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct Cookie {
+    /// The name.
+    pub name: String,
+    /// The value.
+    pub value: String,
+    /// The domain, such as `.example.com`.
+    pub domain: String,
+    /// Only urls at or below the path get the cookie.
+    pub path: String,
+    /// Only sent over https.
+    pub secure: bool,
+    /// Not visible to javascript.
+    pub http_only: bool,
+    /// The expiration time, `None` for a session cookie.
+    pub expires: Option<SystemTime>,
+}
+
+impl Cookie {
+    /// Create a session cookie for all paths of the domain.
+    pub fn new(name: &str, value: &str, domain: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            value: value.to_string(),
+            domain: domain.to_string(),
+            path: "/".to_string(),
+            secure: false,
+            http_only: false,
+            expires: None,
+        }
+    }
+
+    /// Check if the cookie is kept only until miniblink exits.
+    pub fn is_session(&self) -> bool {
+        self.expires.is_none()
+    }
+
+    /// Check if the cookie is expired.
+    pub fn is_expired(&self) -> bool {
+        self.expires.is_some_and(|x| x <= SystemTime::now())
+    }
+
+    /// Check if the cookie would be sent with a request to `url`.
+    pub fn matches(&self, url: &str) -> bool {
+        let Some((scheme, host, path)) = split_url(url) else {
+            return false;
+        };
+        if self.secure && !scheme.eq_ignore_ascii_case("https") {
+            return false;
+        }
+        let domain_matches = match self.domain.strip_prefix('.') {
+            Some(domain) => {
+                host.eq_ignore_ascii_case(domain)
+                    || host
+                        .to_ascii_lowercase()
+                        .ends_with(&self.domain.to_ascii_lowercase())
+            }
+            None => host.eq_ignore_ascii_case(&self.domain),
+        };
+        let path_matches = path == self.path
+            || (path.starts_with(&self.path)
+                && (self.path.ends_with('/') || path[self.path.len()..].starts_with('/')));
+        domain_matches && path_matches
+    }
+
+    /// Parse a line of a curl or Netscape cookie jar.
+    ///
+    /// The fields are separated by tabs: domain, include subdomains, path, secure, expires, name
+    /// and value. A `#HttpOnly_` prefix of the domain marks a http only cookie and an expiration
+    /// of `0` a session cookie.
+    pub fn parse_jar_line(line: &str) -> MBResult<Self> {
+        let invalid = || MBError::InvalidCookie(line.to_string());
+        let line = line.trim_end_matches(['\r', '\n']);
+        let (line, http_only) = match line.strip_prefix("#HttpOnly_") {
+            Some(line) => (line, true),
+            None => (line, false),
+        };
+        let fields: Vec<&str> = line.split('\t').collect();
+        // curl omits the last tab of an empty value in old jars.
+        let [domain, subdomains, path, secure, expires, name, value @ ..] = fields.as_slice()
+        else {
+            return Err(invalid());
+        };
+        let value = match value {
+            [] => "",
+            [value] => value,
+            _ => return Err(invalid()),
+        };
+        if domain.is_empty() || name.is_empty() {
+            return Err(invalid());
+        }
+        let domain = match (*subdomains, domain.starts_with('.')) {
+            ("TRUE", false) => format!(".{domain}"),
+            ("TRUE" | "FALSE", _) => domain.to_string(),
+            _ => return Err(invalid()),
+        };
+        let expires = match expires.parse::<i64>().map_err(|_| invalid())? {
+            secs if secs <= 0 => None,
+            secs => Some(UNIX_EPOCH + Duration::from_secs(secs as u64)),
+        };
+        Ok(Self {
+            name: name.to_string(),
+            value: value.to_string(),
+            domain,
+            path: path.to_string(),
+            secure: *secure == "TRUE",
+            http_only,
+            expires,
+        })
+    }
+
+    /// Format as a line of a curl cookie jar, see `parse_jar_line`.
+    pub fn to_jar_line(&self) -> String {
+        self.try_to_jar_line().unwrap()
+    }
+
+    /// Fallible version of `to_jar_line`.
+    pub fn try_to_jar_line(&self) -> MBResult<String> {
+        self.check()?;
+        Ok(format!(
+            "{}{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            if self.http_only { "#HttpOnly_" } else { "" },
+            self.domain,
+            jar_bool(self.domain.starts_with('.')),
+            self.path,
+            jar_bool(self.secure),
+            self.expires.map_or(0, unix_secs),
+            self.name,
+            self.value
+        ))
+    }
+
+    /// Format as a `Set-Cookie` header value, the format `WebView::set_cookie` accepts.
+    ///
+    /// ```
+    /// use std::time::{Duration, UNIX_EPOCH};
+    ///
+    /// use miniblink::types::Cookie;
+    ///
+    /// let mut cookie = Cookie::new("sid", "abc", ".example.com");
+    /// cookie.expires = Some(UNIX_EPOCH + Duration::from_secs(1_700_000_000));
+    /// cookie.http_only = true;
+    /// assert_eq!(
+    ///     cookie.to_set_cookie(),
+    ///     "sid=abc; domain=.example.com; path=/; expires=Tue, 14 Nov 2023 22:13:20 GMT; HttpOnly"
+    /// );
+    /// ```
+    pub fn to_set_cookie(&self) -> String {
+        self.try_to_set_cookie().unwrap()
+    }
+
+    /// Fallible version of `to_set_cookie`.
+    pub fn try_to_set_cookie(&self) -> MBResult<String> {
+        self.check()?;
+        let mut cookie = format!("{}={}", self.name, self.value);
+        if self.domain.starts_with('.') {
+            cookie += &format!("; domain={}", self.domain);
+        }
+        cookie += &format!("; path={}", self.path);
+        if let Some(expires) = self.expires {
+            cookie += &format!("; expires={}", http_date(unix_secs(expires)));
+        }
+        if self.secure {
+            cookie += "; secure";
+        }
+        if self.http_only {
+            cookie += "; HttpOnly";
+        }
+        Ok(cookie)
+    }
+
+    /// Check that the fields can be written as a `Set-Cookie` header and a jar line.
+    ///
+    /// A `;` would start another attribute, a `=` in the name would move it into the value, and
+    /// tabs and line breaks would split the jar line.
+    fn check(&self) -> MBResult<()> {
+        let valid = !self.name.is_empty()
+            && !self.name.contains('=')
+            && ![&self.name, &self.value, &self.domain, &self.path]
+                .iter()
+                .any(|x| x.contains([';', '\t', '\r', '\n']));
+        match valid {
+            true => Ok(()),
+            false => Err(MBError::InvalidCookie(
+                format!("{}={}", self.name, self.value)
+                    .escape_debug()
+                    .to_string(),
+            )),
+        }
+    }
+
+    /// Get a url of the domain and path, used to set the cookie.
+    pub(crate) fn url(&self) -> String {
+        let scheme = if self.secure { "https" } else { "http" };
+        let host = self.domain.trim_start_matches('.');
+        format!("{scheme}://{host}{}", self.path)
+    }
+}
+
+/// The content of a curl or Netscape cookie jar file.
+///
+/// Blank lines and comments are skipped when parsing, except the `#HttpOnly_` prefix, see
+/// `Cookie::parse_jar_line`. Session cookies are kept with an expiration of `0`. Formatting skips
+/// the cookies which can not be written, see `Cookie::try_to_jar_line`.
+///
+/// ```
+/// use miniblink::types::{Cookie, CookieJar};
+///
+/// let mut jar = CookieJar::default();
+/// jar.cookies.push(Cookie::new("sid", "abc", ".example.com"));
+/// let text = jar.to_string();
+/// assert!(text.starts_with("# Netscape HTTP Cookie File\n"));
+/// assert_eq!(CookieJar::parse(&text).unwrap(), jar);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CookieJar {
+    /// The cookies, in file order.
+    pub cookies: Vec<Cookie>,
+}
+
+impl CookieJar {
+    /// Parse the content of a cookie jar file.
+    pub fn parse(text: &str) -> MBResult<Self> {
+        let cookies = cookie_lines(text)
+            .map(Cookie::parse_jar_line)
+            .collect::<MBResult<_>>()?;
+        Ok(Self { cookies })
+    }
+
+    /// Parse the content of a cookie jar file, skipping the lines which are not valid cookies.
+    pub fn parse_lenient(text: &str) -> Self {
+        let cookies = cookie_lines(text)
+            .filter_map(|x| Cookie::parse_jar_line(x).ok())
+            .collect();
+        Self { cookies }
+    }
+
+    /// Read and parse a cookie jar file.
+    pub fn read<P>(path: P) -> MBResult<Self>
+    where
+        P: AsRef<Path>,
+    {
+        Self::parse(&std::fs::read_to_string(path).map_err(MBError::Io)?)
+    }
+
+    /// Write the cookie jar file, replacing the file if it exists.
+    ///
+    /// Fails without writing if a cookie can not be written, see `Cookie::try_to_jar_line`.
+    pub fn write<P>(&self, path: P) -> MBResult<()>
+    where
+        P: AsRef<Path>,
+    {
+        for cookie in &self.cookies {
+            cookie.check()?;
+        }
+        std::fs::write(path, self.to_string()).map_err(MBError::Io)
+    }
+
+    /// Add the cookie, replacing the cookie with the same domain, path and name.
+    pub fn insert(&mut self, cookie: Cookie) {
+        let same = |x: &Cookie| {
+            x.domain.eq_ignore_ascii_case(&cookie.domain)
+                && x.path == cookie.path
+                && x.name == cookie.name
+        };
+        match self.cookies.iter_mut().find(|x| same(x)) {
+            Some(x) => *x = cookie,
+            None => self.cookies.push(cookie),
+        }
+    }
+}
+
+impl std::fmt::Display for CookieJar {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "# Netscape HTTP Cookie File")?;
+        writeln!(f, "# https://curl.se/docs/http-cookies.html")?;
+        writeln!(f)?;
+        for line in self.cookies.iter().filter_map(|x| x.try_to_jar_line().ok()) {
+            writeln!(f, "{line}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The lines of a cookie jar which are not blank or comments.
+fn cookie_lines(text: &str) -> impl Iterator<Item = &str> {
+    text.lines()
+        .filter(|x| !x.trim().is_empty())
+        .filter(|x| !x.starts_with('#') || x.starts_with("#HttpOnly_"))
+}
+
+fn jar_bool(value: bool) -> &'static str {
+    if value {
+        "TRUE"
+    } else {
+        "FALSE"
+    }
+}
+
+fn unix_secs(time: SystemTime) -> i64 {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(duration) => duration.as_secs() as i64,
+        Err(_) => 0,
+    }
+}
+
+/// Format as `Thu, 01 Jan 1970 00:00:00 GMT`.
+fn http_date(secs: i64) -> String {
+    const WEEKDAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let days = secs.div_euclid(86400);
+    let secs = secs.rem_euclid(86400);
+    // Civil from days, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{}, {:02} {} {} {:02}:{:02}:{:02} GMT",
+        WEEKDAYS[days.rem_euclid(7) as usize],
+        day,
+        MONTHS[month as usize - 1],
+        year,
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    )
+}
+
+/// Split a url into scheme, host and path.
+fn split_url(url: &str) -> Option<(&str, &str, &str)> {
+    let (scheme, rest) = url.split_once("://")?;
+    let end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    let authority = &rest[..end];
+    let host = authority.rsplit('@').next().unwrap_or(authority);
+    let host = match host.rfind(':') {
+        Some(index) if !host.ends_with(']') => &host[..index],
+        _ => host,
+    };
+    let path = &rest[end..];
+    let path = &path[..path.find(['?', '#']).unwrap_or(path.len())];
+    let path = if path.is_empty() { "/" } else { path };
+    Some((scheme, host, path))
+}
@@ -0,0 +1,147 @@
+/// The cursor shown over the page. See `WebView::cursor_type`.
+#[repr(i32)]
+#[derive(Debug, Copy, Clone, Default, Hash, PartialEq, Eq)]
+pub enum CursorType {
+    /// The default arrow.
+    #[default]
+    Pointer = 0,
+    /// A crosshair.
+    Cross = 1,
+    /// A pointing hand, over links.
+    Hand = 2,
+    /// A text cursor.
+    IBeam = 3,
+    /// The busy cursor.
+    Wait = 4,
+    /// An arrow with a question mark.
+    Help = 5,
+    /// Resizing to the east.
+    EastResize = 6,
+    /// Resizing to the north.
+    NorthResize = 7,
+    /// Resizing to the north east.
+    NorthEastResize = 8,
+    /// Resizing to the north west.
+    NorthWestResize = 9,
+    /// Resizing to the south.
+    SouthResize = 10,
+    /// Resizing to the south east.
+    SouthEastResize = 11,
+    /// Resizing to the south west.
+    SouthWestResize = 12,
+    /// Resizing to the west.
+    WestResize = 13,
+    /// Resizing vertically.
+    NorthSouthResize = 14,
+    /// Resizing horizontally.
+    EastWestResize = 15,
+    /// Resizing diagonally, from north east to south west.
+    NorthEastSouthWestResize = 16,
+    /// Resizing diagonally, from north west to south east.
+    NorthWestSouthEastResize = 17,
+    /// A column can be resized.
+    ColumnResize = 18,
+    /// A row can be resized.
+    RowResize = 19,
+    /// Panning in every direction.
+    MiddlePanning = 20,
+    /// Panning to the east.
+    EastPanning = 21,
+    /// Panning to the north.
+    NorthPanning = 22,
+    /// Panning to the north east.
+    NorthEastPanning = 23,
+    /// Panning to the north west.
+    NorthWestPanning = 24,
+    /// Panning to the south.
+    SouthPanning = 25,
+    /// Panning to the south east.
+    SouthEastPanning = 26,
+    /// Panning to the south west.
+    SouthWestPanning = 27,
+    /// Panning to the west.
+    WestPanning = 28,
+    /// Something can be moved.
+    Move = 29,
+    /// A vertical text cursor.
+    VerticalText = 30,
+    /// A table cell can be selected.
+    Cell = 31,
+    /// A context menu is available.
+    ContextMenu = 32,
+    /// An alias or shortcut can be created.
+    Alias = 33,
+    /// The page is busy, but can be used.
+    Progress = 34,
+    /// The item can not be dropped here.
+    NoDrop = 35,
+    /// The item will be copied.
+    Copy = 36,
+    /// No cursor is shown.
+    None = 37,
+    /// The action is not allowed.
+    NotAllowed = 38,
+    /// Something can be zoomed in.
+    ZoomIn = 39,
+    /// Something can be zoomed out.
+    ZoomOut = 40,
+    /// Something can be grabbed.
+    Grab = 41,
+    /// Something is being grabbed.
+    Grabbing = 42,
+    /// A custom image set by the page.
+    Custom = 43,
+}
+
+impl CursorType {
+    pub(crate) fn from_mb(value: i32) -> Option<Self> {
+        let cursor = match value {
+            0 => CursorType::Pointer,
+            1 => CursorType::Cross,
+            2 => CursorType::Hand,
+            3 => CursorType::IBeam,
+            4 => CursorType::Wait,
+            5 => CursorType::Help,
+            6 => CursorType::EastResize,
+            7 => CursorType::NorthResize,
+            8 => CursorType::NorthEastResize,
+            9 => CursorType::NorthWestResize,
+            10 => CursorType::SouthResize,
+            11 => CursorType::SouthEastResize,
+            12 => CursorType::SouthWestResize,
+            13 => CursorType::WestResize,
+            14 => CursorType::NorthSouthResize,
+            15 => CursorType::EastWestResize,
+            16 => CursorType::NorthEastSouthWestResize,
+            17 => CursorType::NorthWestSouthEastResize,
+            18 => CursorType::ColumnResize,
+            19 => CursorType::RowResize,
+            20 => CursorType::MiddlePanning,
+            21 => CursorType::EastPanning,
+            22 => CursorType::NorthPanning,
+            23 => CursorType::NorthEastPanning,
+            24 => CursorType::NorthWestPanning,
+            25 => CursorType::SouthPanning,
+            26 => CursorType::SouthEastPanning,
+            27 => CursorType::SouthWestPanning,
+            28 => CursorType::WestPanning,
+            29 => CursorType::Move,
+            30 => CursorType::VerticalText,
+            31 => CursorType::Cell,
+            32 => CursorType::ContextMenu,
+            33 => CursorType::Alias,
+            34 => CursorType::Progress,
+            35 => CursorType::NoDrop,
+            36 => CursorType::Copy,
+            37 => CursorType::None,
+            38 => CursorType::NotAllowed,
+            39 => CursorType::ZoomIn,
+            40 => CursorType::ZoomOut,
+            41 => CursorType::Grab,
+            42 => CursorType::Grabbing,
+            43 => CursorType::Custom,
+            _ => return None,
+        };
+        Some(cursor)
+    }
+}
@@ -1,14 +1,197 @@
 use std::ffi::c_void;
+use std::sync::Weak;
+
+use crate::error::{MBError, MBResult, UnwrapOrGone};
+use crate::mb_call;
+use crate::mbstring::to_cstring;
+use crate::types::JsValue;
+use crate::webview::{WebView, WebViewInner};
 
 /// The web frame handle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct WebFrameHandle {
     pub(crate) inner: *mut c_void,
 }
 
 unsafe impl Send for WebFrameHandle {}
 
+/// A null handle, returned by the getters of a destroyed webview.
+impl Default for WebFrameHandle {
+    fn default() -> Self {
+        Self {
+            inner: std::ptr::null_mut(),
+        }
+    }
+}
+
 impl WebFrameHandle {
     pub(crate) fn as_ptr(&self) -> *mut c_void {
         self.inner
     }
 }
+
+/// A frame of a webview, such as the main frame or an iframe. See `WebView::frames`.
+///
+/// Frames are tracked through their main world script contexts. A frame becomes stale once its
+/// document is released, for example when it navigates or its iframe is removed. A stale frame
+/// returns `MBError::StaleFrame` instead of passing a dangling handle to miniblink.
+///
+/// ```rust,no_run
+/// use miniblink::{app, webview::WebView};
+///
+/// app::init("./mb.dll").unwrap();
+/// let view = WebView::default();
+/// for iframe in view.main_frame().map(|x| x.children()).unwrap_or_default() {
+///     iframe.insert_css("body { color: red; }");
+/// }
+/// ```
+#[derive(Clone)]
+pub struct Frame {
+    pub(crate) webview: Weak<WebViewInner>,
+    pub(crate) handle: WebFrameHandle,
+    pub(crate) id: u64,
+}
+
+impl Frame {
+    /// Get the webview owning the frame.
+    pub fn webview(&self) -> Option<WebView> {
+        self.webview.upgrade().map(|inner| WebView { inner })
+    }
+
+    /// Check the frame is not stale.
+    pub fn is_alive(&self) -> bool {
+        self.check().is_ok()
+    }
+
+    /// Get the raw handle of the frame.
+    ///
+    /// # Panics
+    /// Panics if the frame is stale, see `try_handle`.
+    pub fn handle(&self) -> WebFrameHandle {
+        self.try_handle().unwrap()
+    }
+
+    /// Fallible version of `handle`.
+    ///
+    /// # Remarks
+    /// Returns `MBError::StaleFrame` if the frame is stale.
+    pub fn try_handle(&self) -> MBResult<WebFrameHandle> {
+        self.check().map(|_| self.handle)
+    }
+
+    /// Check if the frame is the main frame. Stale frames are not.
+    pub fn is_main(&self) -> bool {
+        matches!(self.check(), Ok((_, None)))
+    }
+
+    /// Get the parent frame. Returns `None` for the main frame and stale frames.
+    pub fn parent(&self) -> Option<Frame> {
+        let (webview, parent) = self.check().ok()?;
+        crate::frame::get(&webview, parent?)
+    }
+
+    /// Get the child frames, in creation order. Returns nothing for stale frames.
+    pub fn children(&self) -> Vec<Frame> {
+        match self.check() {
+            Ok((webview, _)) => crate::frame::list(&webview.inner, Some(Some(self.handle))),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    /// Get the URL of the document.
+    pub fn url(&self) -> String {
+        self.try_url().unwrap_or_gone()
+    }
+
+    /// Fallible version of `url`.
+    pub fn try_url(&self) -> MBResult<String> {
+        String::try_from(self.try_eval("window.location.href", false)?)
+    }
+
+    /// Run js in the frame and get the result, see `WebView::eval`.
+    pub fn eval(&self, script: &str, is_in_closure: bool) -> JsValue {
+        self.try_eval(script, is_in_closure).unwrap_or_gone()
+    }
+
+    /// Fallible version of `eval`.
+    pub fn try_eval(&self, script: &str, is_in_closure: bool) -> MBResult<JsValue> {
+        let (webview, _) = self.check()?;
+        webview.try_eval(self.handle, script, is_in_closure)
+    }
+
+    /// Insert a style sheet into the document.
+    pub fn insert_css(&self, css: &str) {
+        self.try_insert_css(css).unwrap_or_gone()
+    }
+
+    /// Fallible version of `insert_css`.
+    pub fn try_insert_css(&self, css: &str) -> MBResult<()> {
+        let (webview, _) = self.check()?;
+        let css = to_cstring(css)?;
+        unsafe {
+            mb_call!(mbInsertCSSByFrame(
+                webview.try_id()?,
+                self.handle.as_ptr(),
+                css.as_ptr()
+            ))
+        }
+    }
+
+    /// Route `window.mbQuery` calls on `channel_id` from this frame to the handler, see
+    /// `WebView::route_query`.
+    ///
+    /// # Remarks
+    /// The handler takes precedence over the one of the webview, and is removed with the document.
+    pub fn route_query<F>(&self, channel_id: i32, handler: F)
+    where
+        F: Fn(&WebView, &str) -> Result<String, String> + Send + 'static,
+    {
+        self.try_route_query(channel_id, handler).unwrap_or_gone()
+    }
+
+    /// Fallible version of `route_query`.
+    pub fn try_route_query<F>(&self, channel_id: i32, handler: F) -> MBResult<()>
+    where
+        F: Fn(&WebView, &str) -> Result<String, String> + Send + 'static,
+    {
+        let (webview, _) = self.check()?;
+        crate::query::route(&webview, channel_id, Some(self.handle), handler)
+    }
+
+    /// Remove the handler set by `route_query`.
+    pub fn remove_query_handler(&self, channel_id: i32) {
+        if let Some(webview) = self.webview() {
+            crate::query::unroute(&webview, channel_id, Some(self.handle));
+        }
+    }
+
+    /// Get the webview and the parent handle, or `MBError::StaleFrame`.
+    fn check(&self) -> MBResult<(WebView, Option<WebFrameHandle>)> {
+        let inner = self.webview.upgrade().ok_or(MBError::StaleFrame)?;
+        let parent = crate::frame::parent(&inner, self)?;
+        Ok((WebView { inner }, parent))
+    }
+}
+
+impl PartialEq for Frame {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Frame {}
+
+impl std::hash::Hash for Frame {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl std::fmt::Debug for Frame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Frame")
+            .field("handle", &self.handle)
+            .field("id", &self.id)
+            .finish()
+    }
+}
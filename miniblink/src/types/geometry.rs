@@ -0,0 +1,42 @@
+use miniblink_sys::mbRect;
+
+/// A rectangle in pixels.
+#[derive(Debug, Copy, Clone, Default, Hash, PartialEq, Eq)]
+pub struct Rect {
+    /// The left edge.
+    pub x: i32,
+    /// The top edge.
+    pub y: i32,
+    /// The width.
+    pub width: i32,
+    /// The height.
+    pub height: i32,
+}
+
+impl Rect {
+    pub(crate) fn from_mb(rect: mbRect) -> Self {
+        Self {
+            x: rect.x,
+            y: rect.y,
+            width: rect.w,
+            height: rect.h,
+        }
+    }
+
+    /// Get the size of the rectangle.
+    pub fn size(&self) -> Size {
+        Size {
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+/// A size in pixels.
+#[derive(Debug, Copy, Clone, Default, Hash, PartialEq, Eq)]
+pub struct Size {
+    /// The width.
+    pub width: i32,
+    /// The height.
+    pub height: i32,
+}
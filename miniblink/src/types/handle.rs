@@ -7,3 +7,12 @@ pub struct WindowHandle {
 }
 
 unsafe impl Send for WindowHandle {}
+
+/// A null handle, returned by `WebView::get_window_handle` of a destroyed webview.
+impl Default for WindowHandle {
+    fn default() -> Self {
+        Self {
+            inner: std::ptr::null_mut(),
+        }
+    }
+}
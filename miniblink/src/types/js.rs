@@ -0,0 +1,200 @@
+use std::ffi::CStr;
+
+use miniblink_sys::{
+    kMbJsTypeBool, kMbJsTypeFrame, kMbJsTypeNull, kMbJsTypeNumber, kMbJsTypeString,
+    kMbJsTypeUndefined, mbJsExecState, mbJsValue,
+};
+
+use crate::error::{MBError, MBResult};
+use crate::mb_call;
+use crate::types::WebFrameHandle;
+
+/// A JavaScript value returned by `WebView::eval`.
+///
+/// Convert it into Rust types with `TryFrom`:
+///
+/// ```
+/// use miniblink::types::JsValue;
+///
+/// let count: i32 = JsValue::Number(2.0).try_into().unwrap();
+/// assert_eq!(count, 2);
+/// assert!(i32::try_from(JsValue::Number(2147483648.0)).is_err());
+/// ```
+#[derive(Debug, Default)]
+pub enum JsValue {
+    /// A number.
+    Number(f64),
+    /// A string.
+    String(String),
+    /// A boolean.
+    Bool(bool),
+    /// `undefined`.
+    #[default]
+    Undefined,
+    /// `null`.
+    Null,
+    /// A frame, such as `window`.
+    Frame(WebFrameHandle),
+    /// Any other value, such as objects and functions.
+    Opaque(OpaqueJsValue),
+}
+
+/// A V8 value kept alive by miniblink until dropped.
+///
+/// # Remarks
+/// The value belongs to the exec state it was returned from, and must be used on the UI thread.
+#[derive(Debug)]
+pub struct OpaqueJsValue {
+    es: mbJsExecState,
+    value: mbJsValue,
+}
+
+impl JsValue {
+    /// Read the value returned by miniblink.
+    #[allow(non_upper_case_globals)]
+    pub(crate) unsafe fn from_raw(es: mbJsExecState, value: mbJsValue) -> MBResult<Self> {
+        let value = match mb_call!(mbGetJsValueType(es, value))? {
+            kMbJsTypeNumber => JsValue::Number(mb_call!(mbJsToDouble(es, value))?),
+            kMbJsTypeString => JsValue::String(js_to_string(es, value)?),
+            kMbJsTypeBool => JsValue::Bool(mb_call!(mbJsToBoolean(es, value))? != 0),
+            kMbJsTypeUndefined => JsValue::Undefined,
+            kMbJsTypeNull => JsValue::Null,
+            kMbJsTypeFrame => JsValue::Frame(WebFrameHandle {
+                inner: mb_call!(mbJsToWebFrameHandle(es, value))?,
+            }),
+            _ => {
+                mb_call!(mbJsValueAddRef(es, value))?;
+                JsValue::Opaque(OpaqueJsValue { es, value })
+            }
+        };
+        Ok(value)
+    }
+
+    /// Get the name of the value type, such as `number`.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            JsValue::Number(_) => "number",
+            JsValue::String(_) => "string",
+            JsValue::Bool(_) => "bool",
+            JsValue::Undefined => "undefined",
+            JsValue::Null => "null",
+            JsValue::Frame(_) => "frame",
+            JsValue::Opaque(_) => "opaque",
+        }
+    }
+
+    /// Check if the value is `undefined` or `null`.
+    pub fn is_nullish(&self) -> bool {
+        matches!(self, JsValue::Undefined | JsValue::Null)
+    }
+
+    fn mismatch(&self, expected: &'static str) -> MBError {
+        MBError::JsTypeMismatch {
+            expected,
+            found: self.type_name(),
+        }
+    }
+}
+
+impl OpaqueJsValue {
+    /// Convert the value to string like JavaScript `String(value)`.
+    pub fn to_js_string(&self) -> MBResult<String> {
+        unsafe { js_to_string(self.es, self.value) }
+    }
+}
+
+impl Clone for OpaqueJsValue {
+    fn clone(&self) -> Self {
+        let _ = unsafe { mb_call!(mbJsValueAddRef(self.es, self.value)) };
+        Self {
+            es: self.es,
+            value: self.value,
+        }
+    }
+}
+
+impl Drop for OpaqueJsValue {
+    fn drop(&mut self) {
+        let _ = unsafe { mb_call!(mbJsValueDeref(self.es, self.value)) };
+    }
+}
+
+unsafe fn js_to_string(es: mbJsExecState, value: mbJsValue) -> MBResult<String> {
+    let result = mb_call!(mbJsToString(es, value))?;
+    match result.is_null() {
+        true => Ok(String::new()),
+        false => Ok(CStr::from_ptr(result).to_string_lossy().to_string()),
+    }
+}
+
+macro_rules! impl_try_from {
+    ($($ty:ty: $expected:literal, $value:pat => $result:expr;)*) => {
+        $(
+            impl TryFrom<JsValue> for $ty {
+                type Error = MBError;
+
+                fn try_from(value: JsValue) -> MBResult<Self> {
+                    match value {
+                        $value => Ok($result),
+                        value => Err(value.mismatch($expected)),
+                    }
+                }
+            }
+
+            impl TryFrom<JsValue> for Option<$ty> {
+                type Error = MBError;
+
+                fn try_from(value: JsValue) -> MBResult<Self> {
+                    match value.is_nullish() {
+                        true => Ok(None),
+                        false => value.try_into().map(Some),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_try_from! {
+    f64: "number", JsValue::Number(x) => x;
+    String: "string", JsValue::String(x) => x;
+    bool: "bool", JsValue::Bool(x) => x;
+    WebFrameHandle: "frame", JsValue::Frame(x) => x;
+    OpaqueJsValue: "opaque", JsValue::Opaque(x) => x;
+}
+
+macro_rules! impl_try_from_integer {
+    ($($ty:ty),*) => {
+        $(
+            impl TryFrom<JsValue> for $ty {
+                type Error = MBError;
+
+                /// Succeeds if the value is an integral number in range.
+                fn try_from(value: JsValue) -> MBResult<Self> {
+                    match value {
+                        JsValue::Number(x)
+                            // `MAX as f64` rounds up to a power of two for 64 bit types.
+                            if x.fract() == 0.0 && x >= <$ty>::MIN as f64 && x < <$ty>::MAX as f64 + 1.0 =>
+                        {
+                            Ok(x as $ty)
+                        }
+                        value => Err(value.mismatch("integer")),
+                    }
+                }
+            }
+
+            impl TryFrom<JsValue> for Option<$ty> {
+                type Error = MBError;
+
+                fn try_from(value: JsValue) -> MBResult<Self> {
+                    match value.is_nullish() {
+                        true => Ok(None),
+                        false => value.try_into().map(Some),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_try_from_integer!(i32, i64, u32);
@@ -0,0 +1,132 @@
+/// The result of loading a frame.
+#[repr(i32)]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub enum LoadingResult {
+    /// The frame is loaded.
+    Succeeded = 0,
+    /// The frame failed to load.
+    Failed = 1,
+    /// The loading is canceled.
+    Canceled = 2,
+}
+
+impl LoadingResult {
+    pub(crate) fn from_mb(result: i32) -> Self {
+        match result {
+            0 => LoadingResult::Succeeded,
+            2 => LoadingResult::Canceled,
+            _ => LoadingResult::Failed,
+        }
+    }
+}
+
+/// Why the main frame failed to load.
+///
+/// Network failures are guessed from the reason reported by miniblink, which
+/// `LoadFailure::reason` keeps as is.
+///
+/// ```rust,no_run
+/// use miniblink::{app, types::LoadFailureKind, webview::WebView};
+///
+/// app::init("./mb.dll").unwrap();
+/// let view = WebView::default();
+/// view.set_error_page_handler(|_, failure| match failure.kind {
+///     LoadFailureKind::NameNotResolved => Some("<h1>You are offline</h1>".into()),
+///     _ => None,
+/// });
+/// ```
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub enum LoadFailureKind {
+    /// The host name could not be resolved.
+    NameNotResolved,
+    /// The server refused the connection.
+    ConnectionRefused,
+    /// The connection timed out.
+    TimedOut,
+    /// The request was blocked.
+    Blocked,
+    /// The server answered with an HTTP error status. See `LoadFailure::status`.
+    Http,
+    /// Any other failure.
+    Other,
+}
+
+impl LoadFailureKind {
+    pub(crate) fn from_reason(reason: &str) -> Self {
+        let reason = reason.to_ascii_lowercase();
+        let has = |patterns: &[&str]| patterns.iter().any(|x| reason.contains(x));
+        if has(&["name_not_resolved", "resolve host", "resolve proxy"]) {
+            LoadFailureKind::NameNotResolved
+        } else if has(&[
+            "connection_refused",
+            "connect to server",
+            "failed to connect",
+        ]) {
+            LoadFailureKind::ConnectionRefused
+        } else if has(&["timed_out", "timed out", "timeout"]) {
+            LoadFailureKind::TimedOut
+        } else if has(&["blocked"]) {
+            LoadFailureKind::Blocked
+        } else {
+            LoadFailureKind::Other
+        }
+    }
+}
+
+/// The load state of the main frame. See `WebView::load_state`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum LoadState {
+    /// Nothing is loaded yet.
+    #[default]
+    Idle,
+    /// The main frame is loading.
+    Loading {
+        /// The url.
+        url: String,
+    },
+    /// The document is ready, and its resources are still loading.
+    DocumentReady {
+        /// The url.
+        url: String,
+    },
+    /// The main frame finished loading.
+    Loaded {
+        /// The url.
+        url: String,
+    },
+    /// The main frame failed to load.
+    Failed {
+        /// The url.
+        url: String,
+        /// The reason.
+        reason: String,
+    },
+    /// The loading is canceled.
+    Canceled {
+        /// The url.
+        url: String,
+    },
+}
+
+impl LoadState {
+    /// Check if the main frame is loading, including its resources.
+    pub fn is_loading(&self) -> bool {
+        matches!(
+            self,
+            LoadState::Loading { .. } | LoadState::DocumentReady { .. }
+        )
+    }
+
+    /// Get the url, or `None` if nothing is loaded yet.
+    pub fn url(&self) -> Option<&str> {
+        match self {
+            LoadState::Idle => None,
+            LoadState::Loading { url }
+            | LoadState::DocumentReady { url }
+            | LoadState::Loaded { url }
+            | LoadState::Failed { url, .. }
+            | LoadState::Canceled { url } => Some(url),
+        }
+    }
+}
@@ -1,21 +1,39 @@
+pub(crate) mod cache;
+pub(crate) mod capabilities;
+pub(crate) mod console;
 pub(crate) mod cookie;
+pub(crate) mod cursor;
 pub(crate) mod download;
+pub(crate) mod geometry;
 pub(crate) mod handle;
+pub(crate) mod js;
 pub(crate) mod keyboard;
 pub(crate) mod frame;
+pub(crate) mod loading;
 pub(crate) mod mouse;
 pub(crate) mod navigation;
+pub(crate) mod payload;
 pub(crate) mod proxy;
 pub(crate) mod request;
+pub(crate) mod settings;
 pub(crate) mod window;
 
+pub use cache::*;
+pub use capabilities::*;
+pub use console::*;
 pub use cookie::*;
+pub use cursor::*;
 pub use download::*;
+pub use geometry::*;
 pub use handle::*;
+pub use js::*;
 pub use keyboard::*;
 pub use frame::*;
+pub use loading::*;
 pub use mouse::*;
 pub use navigation::*;
+pub use payload::*;
 pub use proxy::*;
 pub use request::*;
+pub use settings::*;
 pub use window::*;
@@ -0,0 +1,86 @@
+use crate::error::MBResult;
+use crate::query::json_quote;
+
+/// A value which can be sent to JavaScript by `WebView::emit`.
+///
+/// Strings are sent as JavaScript strings, numbers and booleans as is, and `()` as `null`.
+/// With the `serde` feature, wrap any `Serialize` value in `Json`.
+pub trait EmitPayload {
+    /// Convert to a JavaScript expression.
+    fn to_js(&self) -> MBResult<String>;
+}
+
+impl EmitPayload for str {
+    fn to_js(&self) -> MBResult<String> {
+        Ok(json_quote(self))
+    }
+}
+
+impl EmitPayload for String {
+    fn to_js(&self) -> MBResult<String> {
+        Ok(json_quote(self))
+    }
+}
+
+impl EmitPayload for bool {
+    fn to_js(&self) -> MBResult<String> {
+        Ok(self.to_string())
+    }
+}
+
+impl EmitPayload for () {
+    fn to_js(&self) -> MBResult<String> {
+        Ok("null".into())
+    }
+}
+
+macro_rules! impl_payload_number {
+    ($($ty:ty),*) => {
+        $(
+            impl EmitPayload for $ty {
+                fn to_js(&self) -> MBResult<String> {
+                    Ok(self.to_string())
+                }
+            }
+        )*
+    };
+}
+
+impl_payload_number!(i8, i16, i32, u8, u16, u32);
+
+macro_rules! impl_payload_float {
+    ($($ty:ty),*) => {
+        $(
+            impl EmitPayload for $ty {
+                /// Like `JSON.stringify`, `NaN` and infinities are sent as `null`.
+                fn to_js(&self) -> MBResult<String> {
+                    match self.is_finite() {
+                        true => Ok(self.to_string()),
+                        false => Ok("null".into()),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_payload_float!(f32, f64);
+
+/// Sends the wrapped value serialized as JSON. See `EmitPayload`.
+#[cfg(feature = "serde")]
+pub struct Json<T>(pub T);
+
+#[cfg(feature = "serde")]
+impl<T> EmitPayload for Json<T>
+where
+    T: serde::Serialize,
+{
+    fn to_js(&self) -> MBResult<String> {
+        let json = serde_json::to_string(&self.0)
+            .map_err(|error| crate::error::MBError::InvalidPayload(error.to_string()))?;
+        // JSON allows the line separators in strings, but older JavaScript engines do not.
+        Ok(json
+            .replace('\u{2028}', "\\u2028")
+            .replace('\u{2029}', "\\u2029"))
+    }
+}
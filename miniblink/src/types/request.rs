@@ -9,10 +9,11 @@ pub enum AsynRequestState {
 }
 
 #[repr(i32)]
-#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, Default, Hash, PartialEq, Eq)]
 /// The request type.
 pub enum RequestType {
     /// The request type is invalid.
+    #[default]
     Invalidation = 0,
     /// The request type is get.
     Get = 1,
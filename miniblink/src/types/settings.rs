@@ -0,0 +1,215 @@
+use std::ffi::{c_void, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use miniblink_sys::{
+    mbSettings, MB_ENABLE_DISABLE_CC, MB_ENABLE_DISABLE_H5VIDEO, MB_ENABLE_DISABLE_PDFVIEW,
+    MB_ENABLE_ENABLE_SWIFTSHAER, MB_ENABLE_NODEJS, MB_SETTING_PROXY,
+};
+use widestring::U16CString;
+
+use crate::error::{MBError, MBResult};
+use crate::types::Proxy;
+
+/// The settings used to initialize miniblink. See `app::init_with_settings`.
+///
+/// ```rust,no_run
+/// use miniblink::{app, types::InitSettings};
+///
+/// let settings = InitSettings::new()
+///     .enable_nodejs(true)
+///     .disable_pdf_view(true)
+///     .on_blink_thread_init(|| println!("blink thread started"));
+/// app::init_with_settings("./mb.dll", settings).unwrap();
+/// ```
+#[derive(Default)]
+pub struct InitSettings {
+    /// The global proxy.
+    pub proxy: Option<Proxy>,
+    /// Whether nodejs is enabled.
+    pub enable_nodejs: bool,
+    /// Whether html5 video is disabled.
+    pub disable_h5_video: bool,
+    /// Whether the pdf viewer is disabled.
+    pub disable_pdf_view: bool,
+    /// Whether cc is disabled.
+    pub disable_cc: bool,
+    /// Whether swiftshader is enabled. This is a test feature of miniblink.
+    pub enable_swiftshader: bool,
+    /// The path of the main dll.
+    pub main_dll_path: Option<String>,
+    /// The config string.
+    pub config: Option<String>,
+    /// Called on the blink thread once it is initialized.
+    pub blink_thread_init: Option<Box<dyn Fn() + Send + Sync + 'static>>,
+}
+
+impl InitSettings {
+    /// Create the default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the global proxy.
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Enable nodejs.
+    pub fn enable_nodejs(mut self, enabled: bool) -> Self {
+        self.enable_nodejs = enabled;
+        self
+    }
+
+    /// Disable html5 video.
+    pub fn disable_h5_video(mut self, disabled: bool) -> Self {
+        self.disable_h5_video = disabled;
+        self
+    }
+
+    /// Disable the pdf viewer.
+    pub fn disable_pdf_view(mut self, disabled: bool) -> Self {
+        self.disable_pdf_view = disabled;
+        self
+    }
+
+    /// Disable cc.
+    pub fn disable_cc(mut self, disabled: bool) -> Self {
+        self.disable_cc = disabled;
+        self
+    }
+
+    /// Enable swiftshader. This is a test feature of miniblink.
+    pub fn enable_swiftshader(mut self, enabled: bool) -> Self {
+        self.enable_swiftshader = enabled;
+        self
+    }
+
+    /// Set the path of the main dll.
+    pub fn main_dll_path(mut self, path: &str) -> Self {
+        self.main_dll_path = Some(path.to_string());
+        self
+    }
+
+    /// Set the config string.
+    pub fn config(mut self, config: &str) -> Self {
+        self.config = Some(config.to_string());
+        self
+    }
+
+    /// Set the callback called on the blink thread once it is initialized.
+    pub fn on_blink_thread_init<F>(mut self, callback: F) -> Self
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        self.blink_thread_init = Some(Box::new(callback));
+        self
+    }
+
+    /// Check the settings can be passed to miniblink.
+    pub fn validate(&self) -> MBResult<()> {
+        fn check(valid: bool, message: &str) -> MBResult<()> {
+            match valid {
+                true => Ok(()),
+                false => Err(MBError::InvalidSettings(message.into())),
+            }
+        }
+
+        if let Some(proxy) = &self.proxy {
+            check(
+                proxy.hostname.len() < 100,
+                "proxy hostname must be less than 100 bytes",
+            )?;
+            check(
+                proxy.username.len() < 50,
+                "proxy username must be less than 50 bytes",
+            )?;
+            check(
+                proxy.password.len() < 50,
+                "proxy password must be less than 50 bytes",
+            )?;
+            check(
+                ![&proxy.hostname, &proxy.username, &proxy.password]
+                    .iter()
+                    .any(|x| x.contains('\0')),
+                "proxy must not contain nul bytes",
+            )?;
+        }
+        if let Some(path) = &self.main_dll_path {
+            check(!path.is_empty(), "main dll path must not be empty")?;
+            check(
+                !path.contains('\0'),
+                "main dll path must not contain nul bytes",
+            )?;
+        }
+        if let Some(config) = &self.config {
+            check(!config.contains('\0'), "config must not contain nul bytes")?;
+        }
+        Ok(())
+    }
+
+    /// Convert to `mbSettings`. The settings must be validated and live as long as the library.
+    pub(crate) fn to_native(&'static self) -> NativeInitSettings {
+        let mut mask = 0;
+        for (enabled, flag) in [
+            (self.proxy.is_some(), MB_SETTING_PROXY),
+            (self.enable_nodejs, MB_ENABLE_NODEJS),
+            (self.disable_h5_video, MB_ENABLE_DISABLE_H5VIDEO),
+            (self.disable_pdf_view, MB_ENABLE_DISABLE_PDFVIEW),
+            (self.disable_cc, MB_ENABLE_DISABLE_CC),
+            (self.enable_swiftshader, MB_ENABLE_ENABLE_SWIFTSHAER),
+        ] {
+            if enabled {
+                mask |= flag;
+            }
+        }
+
+        let main_dll_path = self
+            .main_dll_path
+            .as_deref()
+            .map(U16CString::from_str_truncate);
+        let config = self
+            .config
+            .clone()
+            .map(|config| CString::new(config).unwrap_or_default());
+
+        extern "system" fn blink_thread_init(param: *mut c_void) {
+            let settings = unsafe { &*(param as *const InitSettings) };
+            if let Some(callback) = &settings.blink_thread_init {
+                let _ = catch_unwind(AssertUnwindSafe(callback));
+            }
+        }
+
+        let mut settings: mbSettings = unsafe { std::mem::zeroed() };
+        if let Some(proxy) = &self.proxy {
+            settings.proxy = proxy.to_mb_proxy();
+        }
+        settings.mask = mask as _;
+        if self.blink_thread_init.is_some() {
+            settings.blinkThreadInitCallback = Some(blink_thread_init);
+            settings.blinkThreadInitCallbackParam = self as *const _ as *mut c_void;
+        }
+        if let Some(path) = &main_dll_path {
+            settings.mainDllPath = path.as_ptr();
+        }
+        if let Some(config) = &config {
+            settings.config = config.as_ptr();
+        }
+
+        NativeInitSettings {
+            settings,
+            _main_dll_path: main_dll_path,
+            _config: config,
+        }
+    }
+}
+
+/// Owns the buffers referenced by `mbSettings`.
+pub(crate) struct NativeInitSettings {
+    pub(crate) settings: mbSettings,
+    _main_dll_path: Option<U16CString>,
+    _config: Option<CString>,
+}
+
+unsafe impl Send for NativeInitSettings {}
+unsafe impl Sync for NativeInitSettings {}
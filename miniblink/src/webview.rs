@@ -1,16 +1,30 @@
-use std::any::Any;
-use std::collections::HashSet;
+use std::any::{Any, TypeId};
+use std::collections::{HashMap, HashSet};
 use std::ffi::*;
 use std::hash::Hash;
 use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex, Weak};
 
 use crate::app::invoke_command_sync;
-use crate::call_api_or_panic;
+use crate::call_backend;
 use crate::callback::*;
-use crate::mbstring::MbString;
+use crate::command::CommandRouter;
+use crate::cookie::CookieManager;
+use crate::emit::EmitQueue;
+use crate::error::{MBError, MBResult, UnwrapOrGone};
+use crate::events::{self, Event, ScriptContextCreated, Subscription};
+use crate::frame::FrameTree;
+use crate::future::{Completer, MBFuture};
+use crate::history::{History, VisitedList};
+use crate::load::LoadTracker;
+use crate::mb_call;
+use crate::mbstring::{from_utf8_parts, from_utf8_ptr, to_cstring, to_wide_cstring, MbString};
 use crate::net_job::NetJob;
 use crate::params::*;
+use crate::profile::{Profile, Storage};
+use crate::query::QueryRouter;
 use crate::types::*;
 
 /// Webview ID.
@@ -19,15 +33,41 @@ pub type WebViewID = miniblink_sys::mbWebView;
 /// Wraps to WebView
 #[repr(transparent)]
 pub struct WebView {
-    inner: Arc<WebViewInner>,
+    pub(crate) inner: Arc<WebViewInner>,
 }
 
 /// Wraps to WebView.
 pub(crate) struct WebViewInner {
     pub(crate) id: WebViewID,
-    pub(crate) callbacks: Mutex<Vec<Box<dyn Any>>>,
+    pub(crate) callbacks: Mutex<HashMap<&'static str, StoredCallback>>,
+    pub(crate) events: Mutex<HashMap<TypeId, Box<dyn Any + Send>>>,
+    pub(crate) queries: Mutex<QueryRouter>,
+    pub(crate) commands: Mutex<CommandRouter>,
+    pub(crate) emits: Mutex<EmitQueue>,
+    pub(crate) frames: Mutex<FrameTree>,
+    pub(crate) load: Mutex<LoadTracker>,
+    pub(crate) history: Mutex<VisitedList>,
+    pub(crate) cookie_jar: Mutex<Option<PathBuf>>,
+    pub(crate) storage: Mutex<Option<Storage>>,
+    pub(crate) disk_cache: Mutex<Option<PathBuf>>,
     pub(crate) parent: Mutex<Option<Weak<WebViewInner>>>,
     pub(crate) childset: Mutex<HashSet<WebView>>,
+    pub(crate) destroyed: AtomicBool,
+}
+
+/// Every webview created by this crate, used to tear down in `app::uninit`.
+static WEBVIEWS: Mutex<Vec<Weak<WebViewInner>>> = Mutex::new(Vec::new());
+
+/// Get the webviews created by this crate which are not destroyed.
+pub(crate) fn live_webviews() -> Vec<WebView> {
+    WEBVIEWS
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|x| x.upgrade())
+        .map(|inner| WebView { inner })
+        .filter(|x| !x.is_destroyed())
+        .collect()
 }
 
 pub(crate) struct CallBackContext<T> {
@@ -42,9 +82,67 @@ where
     pub(crate) fn new(webview: &WebView, content: T) -> Box<Self> {
         Box::new(CallBackContext {
             webview: Arc::downgrade(&webview.inner),
-            content: content,
+            content,
         })
     }
+
+    /// Take a new reference to the context passed to a shim.
+    ///
+    /// # Remarks
+    /// The reference keeps the closure alive even if the callback is revoked while it runs.
+    pub(crate) unsafe fn from_param(param: *mut c_void) -> Arc<Self> {
+        let param = param as *const Self;
+        Arc::increment_strong_count(param);
+        Arc::from_raw(param)
+    }
+}
+
+/// A callback context registered through a `mbOn*` setter.
+pub(crate) struct StoredCallback {
+    token: u64,
+    unset: Unset,
+    _context: Arc<dyn Any + Send>,
+}
+
+/// How a stored callback is unregistered.
+enum Unset {
+    /// Pass `None` to the native setter.
+    Native(fn(WebViewID)),
+    /// Remove the listener with the token from the event bus.
+    Listener(fn(&WebViewInner, u64)),
+}
+
+// The context is only shared with the shims, which run on the UI thread.
+unsafe impl Send for StoredCallback {}
+
+impl StoredCallback {
+    fn unset(&self, inner: &WebViewInner) {
+        match self.unset {
+            Unset::Native(unset) => {
+                if !inner.destroyed.load(Ordering::SeqCst) {
+                    unset(inner.id);
+                }
+            }
+            Unset::Listener(unset) => unset(inner, self.token),
+        }
+    }
+}
+
+static NEXT_CALLBACK_TOKEN: AtomicU64 = AtomicU64::new(1);
+
+/// A callback stored by `store_callback_context`, not yet registered in miniblink.
+struct PendingCallback {
+    handle: CallbackHandle,
+    previous: Option<StoredCallback>,
+}
+
+impl PendingCallback {
+    /// Free the replaced context. Call after passing the new shim to miniblink, which may still
+    /// call the previous one until then.
+    fn registered(self) -> CallbackHandle {
+        drop(self.previous);
+        self.handle
+    }
 }
 
 impl WebView {
@@ -53,8 +151,16 @@ impl WebView {
     /// # Remarks
     /// This is for advanced users. It is recommended to use `WebViewWindow` to create a webview.
     pub fn new_offscreen() -> Self {
-        let inner = unsafe { call_api_or_panic().mbCreateWebView() };
-        unsafe { Self::from_raw(inner) }
+        Self::try_new_offscreen().unwrap_or_gone()
+    }
+
+    /// Fallible version of `new_offscreen`.
+    pub fn try_new_offscreen() -> MBResult<Self> {
+        let inner = unsafe { mb_call!(mbCreateWebView()) }?;
+        match inner {
+            0 => Err(MBError::CreateWebViewFailed),
+            inner => Ok(unsafe { Self::from_raw(inner) }),
+        }
     }
 
     /// Retake the inner pointer.
@@ -76,39 +182,140 @@ impl WebView {
         // }
         let webview = WebViewInner {
             id: ptr,
-            callbacks: Mutex::new(Vec::new()),
+            callbacks: Mutex::new(HashMap::new()),
+            events: Mutex::new(HashMap::new()),
+            queries: Mutex::default(),
+            commands: Mutex::default(),
+            emits: Mutex::default(),
+            frames: Mutex::default(),
+            load: Mutex::default(),
+            history: Mutex::default(),
+            cookie_jar: Mutex::new(None),
+            storage: Mutex::new(None),
+            disk_cache: Mutex::new(None),
             parent: Mutex::new(None),
             childset: Mutex::new(HashSet::new()),
+            destroyed: AtomicBool::new(false),
         };
-        let webview = WebView {
-            inner: Arc::new(webview),
-        };
+        let inner = Arc::new(webview);
+        let mut webviews = WEBVIEWS.lock().unwrap();
+        webviews.retain(|x| x.strong_count() > 0);
+        webviews.push(Arc::downgrade(&inner));
+        drop(webviews);
+        let webview = WebView { inner };
+        // Older libraries lack the script context callbacks, and then no frame is tracked.
+        let _ = crate::frame::track(&webview);
+        let _ = crate::load::track(&webview);
+        let _ = crate::history::track(&webview);
         webview
     }
 
+    /// Destroy the webview. Child webviews are destroyed first.
+    ///
+    /// # Remarks
+    /// The webview can not be used any more: the fallible methods return `MBError::Destroyed`
+    /// without calling miniblink, and the others do nothing and return a default value.
+    /// Callbacks are freed once every handle is dropped. Does nothing if the webview is already
+    /// destroyed.
+    ///
+    /// ```rust,no_run
+    /// use miniblink::{app, webview::WebView};
+    ///
+    /// app::init("./mb.dll").unwrap();
+    /// let view = WebView::default();
+    /// view.destroy();
+    /// assert!(view.is_destroyed());
+    /// ```
+    pub fn destroy(&self) {
+        self.inner.destroy();
+    }
+
+    /// Check if the webview is destroyed.
+    pub fn is_destroyed(&self) -> bool {
+        self.inner.destroyed.load(Ordering::SeqCst)
+    }
+
+    /// Destroy every live webview, children before parents, and free the callbacks.
+    pub(crate) fn destroy_all() {
+        let webviews: Vec<WebView> = WEBVIEWS
+            .lock()
+            .unwrap()
+            .drain(..)
+            .filter_map(|x| x.upgrade())
+            .map(|inner| WebView { inner })
+            .collect();
+        for webview in webviews.iter().filter(|x| x.parent().is_none()) {
+            webview.destroy();
+        }
+        for webview in &webviews {
+            webview.destroy();
+            webview.inner.events.lock().unwrap().clear();
+            std::mem::take(&mut *webview.inner.queries.lock().unwrap());
+            std::mem::take(&mut *webview.inner.commands.lock().unwrap());
+            std::mem::take(&mut *webview.inner.emits.lock().unwrap());
+            std::mem::take(&mut *webview.inner.frames.lock().unwrap());
+            std::mem::take(&mut *webview.inner.load.lock().unwrap());
+            std::mem::take(&mut *webview.inner.history.lock().unwrap());
+            webview.inner.callbacks.lock().unwrap().clear();
+        }
+    }
+
     /// Get the inner id.
     pub fn as_id(&self) -> WebViewID {
         self.inner.id
     }
 
+    /// Get the inner id, or `MBError::Destroyed` once the webview is destroyed.
+    pub(crate) fn try_id(&self) -> MBResult<WebViewID> {
+        match self.is_destroyed() {
+            true => Err(MBError::Destroyed),
+            false => Ok(self.inner.id),
+        }
+    }
+
     /// Stop loading the page.
     pub fn stop_loading(&self) {
-        unsafe { call_api_or_panic().mbStopLoading(self.as_id()) }
+        self.try_stop_loading().unwrap_or_gone()
+    }
+
+    /// Fallible version of `stop_loading`.
+    pub fn try_stop_loading(&self) -> MBResult<()> {
+        unsafe { mb_call!(mbStopLoading(self.try_id()?)) }
     }
 
     /// Reload page.
     pub fn reload(&self) {
-        unsafe { call_api_or_panic().mbReload(self.as_id()) }
+        self.try_reload().unwrap_or_gone()
+    }
+
+    /// Fallible version of `reload`.
+    pub fn try_reload(&self) -> MBResult<()> {
+        unsafe { mb_call!(mbReload(self.try_id()?)) }
     }
 
     /// Go back.
     pub fn go_back(&self) {
-        unsafe { call_api_or_panic().mbGoBack(self.as_id()) }
+        self.try_go_back().unwrap_or_gone()
+    }
+
+    /// Fallible version of `go_back`.
+    pub fn try_go_back(&self) -> MBResult<()> {
+        unsafe { mb_call!(mbGoBack(self.try_id()?)) }
+    }
+
+    /// Get the navigation history, see `history::History`.
+    pub fn history(&self) -> History {
+        History::new(self)
     }
 
     /// Go forward.
     pub fn go_forward(&self) {
-        unsafe { call_api_or_panic().mbGoForward(self.as_id()) }
+        self.try_go_forward().unwrap_or_gone()
+    }
+
+    /// Fallible version of `go_forward`.
+    pub fn try_go_forward(&self) -> MBResult<()> {
+        unsafe { mb_call!(mbGoForward(self.try_id()?)) }
     }
 
     /// Resize the page.
@@ -117,56 +324,105 @@ impl WebView {
     ///
     /// This api will resize the window at the same time if using the internal api to create window.
     pub fn resize(&self, w: i32, h: i32) {
-        unsafe { call_api_or_panic().mbResize(self.as_id(), w, h) }
+        self.try_resize(w, h).unwrap_or_gone()
+    }
+
+    /// Fallible version of `resize`.
+    pub fn try_resize(&self, w: i32, h: i32) -> MBResult<()> {
+        unsafe { mb_call!(mbResize(self.try_id()?, w, h)) }
     }
 
     /// Get the window handle.
     pub fn get_window_handle(&self) -> WindowHandle {
-        let id = self.as_id();
+        self.try_get_window_handle().unwrap_or_gone()
+    }
+
+    /// Fallible version of `get_window_handle`.
+    pub fn try_get_window_handle(&self) -> MBResult<WindowHandle> {
+        let id = self.try_id()?;
         invoke_command_sync(move || unsafe {
-            let inner = call_api_or_panic().mbGetPlatformWindowHandle(id);
-            WindowHandle { inner }
-        })
+            mb_call!(mbGetPlatformWindowHandle(id)).map(|inner| WindowHandle { inner })
+        })?
     }
 
     /// Send select command to editor.
     pub fn editor_select_all(&self) {
-        unsafe { call_api_or_panic().mbEditorSelectAll(self.as_id()) }
+        self.try_editor_select_all().unwrap_or_gone()
+    }
+
+    /// Fallible version of `editor_select_all`.
+    pub fn try_editor_select_all(&self) -> MBResult<()> {
+        unsafe { mb_call!(mbEditorSelectAll(self.try_id()?)) }
     }
 
     /// Send unselect command to editor.
     pub fn editor_unselect(&self) {
-        unsafe { call_api_or_panic().mbEditorUnSelect(self.as_id()) }
+        self.try_editor_unselect().unwrap_or_gone()
+    }
+
+    /// Fallible version of `editor_unselect`.
+    pub fn try_editor_unselect(&self) -> MBResult<()> {
+        unsafe { mb_call!(mbEditorUnSelect(self.try_id()?)) }
     }
 
     /// Send copy command to editor.
     pub fn editor_copy(&self) {
-        unsafe { call_api_or_panic().mbEditorCopy(self.as_id()) }
+        self.try_editor_copy().unwrap_or_gone()
+    }
+
+    /// Fallible version of `editor_copy`.
+    pub fn try_editor_copy(&self) -> MBResult<()> {
+        unsafe { mb_call!(mbEditorCopy(self.try_id()?)) }
     }
 
     /// Send cut command to editor.
     pub fn editor_cut(&self) {
-        unsafe { call_api_or_panic().mbEditorCut(self.as_id()) }
+        self.try_editor_cut().unwrap_or_gone()
+    }
+
+    /// Fallible version of `editor_cut`.
+    pub fn try_editor_cut(&self) -> MBResult<()> {
+        unsafe { mb_call!(mbEditorCut(self.try_id()?)) }
     }
 
     /// Send delete command to editor.
     pub fn editor_delete(&self) {
-        unsafe { call_api_or_panic().mbEditorDelete(self.as_id()) }
+        self.try_editor_delete().unwrap_or_gone()
+    }
+
+    /// Fallible version of `editor_delete`.
+    pub fn try_editor_delete(&self) -> MBResult<()> {
+        unsafe { mb_call!(mbEditorDelete(self.try_id()?)) }
     }
 
     /// Send undo command to editor.
     pub fn editor_undo(&self) {
-        unsafe { call_api_or_panic().mbEditorUndo(self.as_id()) }
+        self.try_editor_undo().unwrap_or_gone()
+    }
+
+    /// Fallible version of `editor_undo`.
+    pub fn try_editor_undo(&self) -> MBResult<()> {
+        unsafe { mb_call!(mbEditorUndo(self.try_id()?)) }
     }
 
     /// Send redo command to editor.
     pub fn editor_redo(&self) {
-        unsafe { call_api_or_panic().mbEditorRedo(self.as_id()) }
+        self.try_editor_redo().unwrap_or_gone()
+    }
+
+    /// Fallible version of `editor_redo`.
+    pub fn try_editor_redo(&self) -> MBResult<()> {
+        unsafe { mb_call!(mbEditorRedo(self.try_id()?)) }
     }
 
     /// Send paste command to editor.
     pub fn editor_paste(&self) {
-        unsafe { call_api_or_panic().mbEditorPaste(self.as_id()) }
+        self.try_editor_paste().unwrap_or_gone()
+    }
+
+    /// Fallible version of `editor_paste`.
+    pub fn try_editor_paste(&self) -> MBResult<()> {
+        unsafe { mb_call!(mbEditorPaste(self.try_id()?)) }
     }
 
     /// Get the page cookies asynchronously.
@@ -174,11 +430,20 @@ impl WebView {
     /// # Remarks
     /// Cookie information will be returned in the callback function.
     pub fn get_cookie_async<F>(&self, callback: F)
+    where
+        F: FnOnce(&WebView, &Option<String>) + Send + 'static,
+    {
+        self.try_get_cookie_async(callback).unwrap_or_gone()
+    }
+
+    /// Fallible version of `get_cookie_async`.
+    pub fn try_get_cookie_async<F>(&self, callback: F) -> MBResult<()>
     where
         F: FnOnce(&WebView, &Option<String>) + Send + 'static,
     {
         use std::ffi::{c_int, c_void};
 
+        let api = crate::call_symbol("mbGetCookie")?;
         let context = CallBackContext::new(self, callback);
 
         extern "system" fn shim<F>(
@@ -201,28 +466,198 @@ impl WebView {
             }
         }
 
+        let id = self.try_id()?;
         let param = Box::into_raw(Box::new(context));
 
-        unsafe {
-            call_api_or_panic().mbGetCookie(
-                self.as_id(),
-                Some(shim::<F>),
-                param as *mut std::ffi::c_void,
-            )
-        };
+        unsafe { api.mbGetCookie(id, Some(shim::<F>), param as *mut c_void) };
+        Ok(())
     }
 
     /// Get the page cookies.
     pub fn get_cookie(&self) -> Option<String> {
-        let ptr = self.as_id();
+        self.try_get_cookie().unwrap_or_gone()
+    }
+
+    /// Fallible version of `get_cookie`.
+    pub fn try_get_cookie(&self) -> MBResult<Option<String>> {
+        let ptr = self.try_id()?;
         invoke_command_sync(move || {
-            let cookie = unsafe { call_api_or_panic().mbGetCookieOnBlinkThread(ptr) };
+            let cookie = unsafe { mb_call!(mbGetCookieOnBlinkThread(ptr)) }?;
             if cookie.is_null() {
-                None
+                Ok(None)
             } else {
-                Some(unsafe { CStr::from_ptr(cookie).to_string_lossy().to_string() })
+                Ok(Some(unsafe {
+                    CStr::from_ptr(cookie).to_string_lossy().to_string()
+                }))
             }
-        })
+        })?
+    }
+
+    /// Get the page cookies without blocking.
+    pub fn cookies(&self) -> MBFuture<Option<String>> {
+        let (future, completer) = MBFuture::pending();
+        match self.try_get_cookie_async(move |_, cookie| completer.complete(Ok(cookie.clone()))) {
+            Ok(()) => future,
+            Err(error) => MBFuture::ready(Err(error)),
+        }
+    }
+
+    /// Get the page source without blocking.
+    pub fn source(&self) -> MBFuture<String> {
+        let (future, completer) = MBFuture::pending();
+        let id = match self.try_id() {
+            Ok(id) => id,
+            Err(error) => return MBFuture::ready(Err(error)),
+        };
+        let api = match crate::call_symbol("mbGetSource") {
+            Ok(api) => api,
+            Err(error) => return MBFuture::ready(Err(error)),
+        };
+
+        extern "system" fn shim(_: WebViewID, param: *mut c_void, mhtml: *const c_char) {
+            let completer = unsafe { Box::from_raw(param as *mut Completer<String>) };
+            let source = match mhtml.is_null() {
+                true => String::new(),
+                false => unsafe { CStr::from_ptr(mhtml).to_string_lossy().to_string() },
+            };
+            completer.complete(Ok(source));
+        }
+
+        let param = Box::into_raw(Box::new(completer));
+        unsafe { api.mbGetSource(id, Some(shim), param as _) };
+        future
+    }
+
+    /// Get the page source, blocking until miniblink serializes it.
+    ///
+    /// ```rust,no_run
+    /// use miniblink::{app, webview::WebView};
+    ///
+    /// app::init("./mb.dll").unwrap();
+    /// let view = WebView::default();
+    /// view.load_url("https://miniblink.net/");
+    /// println!("{}", view.source_sync());
+    /// ```
+    pub fn source_sync(&self) -> String {
+        self.try_source_sync().unwrap_or_gone()
+    }
+
+    /// Fallible version of `source_sync`.
+    pub fn try_source_sync(&self) -> MBResult<String> {
+        let ptr = self.try_id()?;
+        invoke_command_sync(move || {
+            let source = unsafe { mb_call!(mbGetSourceSync(ptr)) }?;
+            match source.is_null() {
+                true => Ok(String::new()),
+                false => unsafe { MbString::from_raw(source) }.to_string_lossy(),
+            }
+        })?
+    }
+
+    /// Get the serialized markup of the frame without blocking.
+    ///
+    /// # Remarks
+    /// Miniblink has no synchronous version of this api, awaiting the future on the UI thread never
+    /// completes it.
+    ///
+    /// ```rust,no_run
+    /// use miniblink::{error::MBResult, webview::WebView};
+    ///
+    /// // Awaited from a task running off the UI thread.
+    /// async fn markup(view: &WebView) -> MBResult<String> {
+    ///     view.markup(view.get_mainframe()).await
+    /// }
+    /// ```
+    pub fn markup(&self, frame_handle: WebFrameHandle) -> MBFuture<String> {
+        let (future, completer) = MBFuture::pending();
+        let id = match self.try_id() {
+            Ok(id) => id,
+            Err(error) => return MBFuture::ready(Err(error)),
+        };
+        let api = match crate::call_symbol("mbGetContentAsMarkup") {
+            Ok(api) => api,
+            Err(error) => return MBFuture::ready(Err(error)),
+        };
+
+        extern "system" fn shim(
+            _: WebViewID,
+            param: *mut c_void,
+            content: *const c_char,
+            size: usize,
+        ) {
+            let completer = unsafe { Box::from_raw(param as *mut Completer<String>) };
+            completer.complete(Ok(unsafe { from_utf8_parts(content, size) }));
+        }
+
+        let param = Box::into_raw(Box::new(completer));
+        unsafe { api.mbGetContentAsMarkup(id, Some(shim), param as _, frame_handle.inner) };
+        future
+    }
+
+    /// Serialize the page with its resources to MHTML without blocking.
+    ///
+    /// # Remarks
+    /// Miniblink has no synchronous version of this api, awaiting the future on the UI thread never
+    /// completes it.
+    pub fn mhtml(&self) -> MBFuture<String> {
+        let (future, completer) = MBFuture::pending();
+        match self.serialize_to_mhtml(move |mhtml| {
+            completer.complete(Ok(String::from_utf8_lossy(mhtml).into_owned()))
+        }) {
+            Ok(()) => future,
+            Err(error) => MBFuture::ready(Err(error)),
+        }
+    }
+
+    /// Serialize the page to MHTML and write it to `path` without blocking.
+    ///
+    /// The bytes from miniblink are written unchanged, resolves to `MBError::Io` if the file can not
+    /// be written.
+    ///
+    /// ```rust,no_run
+    /// use miniblink::{error::MBResult, webview::WebView};
+    ///
+    /// // Awaited from a task running off the UI thread.
+    /// async fn archive(view: &WebView) -> MBResult<()> {
+    ///     view.save_as_mhtml("./page.mhtml").await
+    /// }
+    /// ```
+    pub fn save_as_mhtml<P>(&self, path: P) -> MBFuture<()>
+    where
+        P: AsRef<std::path::Path>,
+    {
+        let path = path.as_ref().to_path_buf();
+        let (future, completer) = MBFuture::pending();
+        match self.serialize_to_mhtml(move |mhtml| {
+            completer.complete(std::fs::write(&path, mhtml).map_err(MBError::Io))
+        }) {
+            Ok(()) => future,
+            Err(error) => MBFuture::ready(Err(error)),
+        }
+    }
+
+    fn serialize_to_mhtml<F>(&self, callback: F) -> MBResult<()>
+    where
+        F: FnOnce(&[u8]) + 'static,
+    {
+        let api = crate::call_symbol("mbUtilSerializeToMHTML")?;
+
+        extern "system" fn shim<F>(_: WebViewID, param: *mut c_void, mhtml: *const c_char)
+        where
+            F: FnOnce(&[u8]),
+        {
+            let callback = unsafe { Box::from_raw(param as *mut F) };
+            let mhtml = match mhtml.is_null() {
+                true => &[][..],
+                false => unsafe { CStr::from_ptr(mhtml) }.to_bytes(),
+            };
+            let _ = catch_unwind(AssertUnwindSafe(|| callback(mhtml)));
+        }
+
+        let id = self.try_id()?;
+        let param = Box::into_raw(Box::new(callback));
+        unsafe { api.mbUtilSerializeToMHTML(id, Some(shim::<F>), param as _) };
+        Ok(())
     }
 
     /// Set the page cookies.
@@ -231,9 +666,14 @@ impl WebView {
     ///
     /// This cookie should follow the curl format of `PERSONALIZE=123;expires=Monday, 13-Jun-2022 03:04:55 GMT; domain=.fidelity.com; path=/; secure`
     pub fn set_cookie(&self, url: &str, cookie: &str) {
-        let url = CString::new(url).unwrap();
-        let cookie = CString::new(cookie).unwrap();
-        unsafe { call_api_or_panic().mbSetCookie(self.as_id(), url.as_ptr(), cookie.as_ptr()) }
+        self.try_set_cookie(url, cookie).unwrap_or_gone()
+    }
+
+    /// Fallible version of `set_cookie`.
+    pub fn try_set_cookie(&self, url: &str, cookie: &str) -> MBResult<()> {
+        let url = to_cstring(url)?;
+        let cookie = to_cstring(cookie)?;
+        unsafe { mb_call!(mbSetCookie(self.try_id()?, url.as_ptr(), cookie.as_ptr())) }
     }
 
     /// Perform cookie command.
@@ -241,26 +681,119 @@ impl WebView {
     /// # Remarks
     /// This api only affects the curl settings, does not change the javascript content.
     pub fn perform_cookie_command<F>(&self, command: CookieCommand) {
-        unsafe {
-            call_api_or_panic().mbPerformCookieCommand(self.as_id(), command as _);
-        }
+        self.try_perform_cookie_command(command).unwrap_or_gone()
+    }
+
+    /// Fallible version of `perform_cookie_command`.
+    pub fn try_perform_cookie_command(&self, command: CookieCommand) -> MBResult<()> {
+        unsafe { mb_call!(mbPerformCookieCommand(self.try_id()?, command as _)) }
     }
 
     /// Clear all cookies.
     pub fn clear_cookie(&self) {
-        unsafe { call_api_or_panic().mbClearCookie(self.as_id()) };
+        self.try_clear_cookie().unwrap_or_gone()
+    }
+
+    /// Fallible version of `clear_cookie`.
+    pub fn try_clear_cookie(&self) -> MBResult<()> {
+        unsafe { mb_call!(mbClearCookie(self.try_id()?)) }
     }
 
     /// Set cookie jar path.
+    ///
+    /// # Remarks
+    /// The path is a directory, the cookies are stored in `cookies.dat` inside it.
     pub fn set_cookie_jar_path(&self, path: &str) {
-        let path = widestring::WideCString::from_str(path).unwrap();
-        unsafe { call_api_or_panic().mbSetCookieJarPath(self.as_id(), path.as_ptr()) };
+        self.try_set_cookie_jar_path(path).unwrap_or_gone()
+    }
+
+    /// Fallible version of `set_cookie_jar_path`.
+    pub fn try_set_cookie_jar_path(&self, path: &str) -> MBResult<()> {
+        let wide = to_wide_cstring(path)?;
+        unsafe { mb_call!(mbSetCookieJarPath(self.try_id()?, wide.as_ptr())) }?;
+        *self.inner.cookie_jar.lock().unwrap() = Some(PathBuf::from(path).join("cookies.dat"));
+        Ok(())
     }
 
     /// Set cookie jar full path.
     pub fn set_cookie_jar_full_path(&self, path: &str) {
-        let path = widestring::WideCString::from_str(path).unwrap();
-        unsafe { call_api_or_panic().mbSetCookieJarFullPath(self.as_id(), path.as_ptr()) };
+        self.try_set_cookie_jar_full_path(path).unwrap_or_gone()
+    }
+
+    /// Fallible version of `set_cookie_jar_full_path`.
+    pub fn try_set_cookie_jar_full_path(&self, path: &str) -> MBResult<()> {
+        let wide = to_wide_cstring(path)?;
+        unsafe { mb_call!(mbSetCookieJarFullPath(self.try_id()?, wide.as_ptr())) }?;
+        *self.inner.cookie_jar.lock().unwrap() = Some(PathBuf::from(path));
+        Ok(())
+    }
+
+    /// Get the cookie manager, see `cookie::CookieManager`.
+    pub fn cookie_manager(&self) -> CookieManager {
+        CookieManager::new(self)
+    }
+
+    /// Write the cookies, including session cookies, to a cookie jar file at `path`.
+    ///
+    /// # Remarks
+    /// The cookies are flushed to the cookie jar of miniblink first, see `cookie::CookieManager`.
+    pub fn export_cookies<P>(&self, path: P)
+    where
+        P: AsRef<std::path::Path>,
+    {
+        self.try_export_cookies(path).unwrap_or_gone()
+    }
+
+    /// Fallible version of `export_cookies`.
+    pub fn try_export_cookies<P>(&self, path: P) -> MBResult<()>
+    where
+        P: AsRef<std::path::Path>,
+    {
+        self.cookie_manager().flush()?.write(path)
+    }
+
+    /// Add the cookies of the cookie jar file at `path`, replacing those with the same domain,
+    /// path and name.
+    ///
+    /// # Remarks
+    /// The cookies are merged into the cookie jar of miniblink, which is then reloaded.
+    ///
+    /// ```rust,no_run
+    /// use miniblink::{app, webview::WebView};
+    ///
+    /// app::init("./mb.dll").unwrap();
+    /// let view = WebView::default();
+    /// view.import_cookies("./cookies.txt");
+    /// ```
+    pub fn import_cookies<P>(&self, path: P)
+    where
+        P: AsRef<std::path::Path>,
+    {
+        self.try_import_cookies(path).unwrap_or_gone()
+    }
+
+    /// Fallible version of `import_cookies`.
+    pub fn try_import_cookies<P>(&self, path: P) -> MBResult<()>
+    where
+        P: AsRef<std::path::Path>,
+    {
+        let imported = CookieJar::read(path)?;
+        let manager = self.cookie_manager();
+        let mut jar = manager.flush()?;
+        for cookie in imported.cookies {
+            jar.insert(cookie);
+        }
+        manager.reload(&jar)
+    }
+
+    /// Use the profile for the cookies, local storage and disk cache, see `profile::Profile`.
+    pub fn set_profile(&self, profile: &Profile) {
+        self.try_set_profile(profile).unwrap_or_gone()
+    }
+
+    /// Fallible version of `set_profile`.
+    pub fn try_set_profile(&self, profile: &Profile) -> MBResult<()> {
+        profile.apply(self)
     }
 
     /// Set local storage full path.
@@ -269,15 +802,39 @@ impl WebView {
     ///
     /// Only directory paths can be set, file paths cannot be set.
     pub fn set_local_storage_full_path(&self, path: &str) {
-        let path = widestring::WideCString::from_str(path).unwrap();
-        unsafe { call_api_or_panic().mbSetLocalStorageFullPath(self.as_id(), path.as_ptr()) };
+        self.try_set_local_storage_full_path(path).unwrap_or_gone()
+    }
+
+    /// Fallible version of `set_local_storage_full_path`.
+    pub fn try_set_local_storage_full_path(&self, path: &str) -> MBResult<()> {
+        let path = to_wide_cstring(path)?;
+        unsafe { mb_call!(mbSetLocalStorageFullPath(self.try_id()?, path.as_ptr())) }
     }
 
     /// Fire mouse event.
     pub fn fire_mouse_event(&self, message: WindowMessage, x: i32, y: i32, flags: MouseFlags) {
+        self.try_fire_mouse_event(message, x, y, flags)
+            .unwrap_or_gone()
+    }
+
+    /// Fallible version of `fire_mouse_event`.
+    pub fn try_fire_mouse_event(
+        &self,
+        message: WindowMessage,
+        x: i32,
+        y: i32,
+        flags: MouseFlags,
+    ) -> MBResult<()> {
         unsafe {
-            call_api_or_panic().mbFireMouseEvent(self.as_id(), message as _, x, y, flags.into())
-        };
+            mb_call!(mbFireMouseEvent(
+                self.try_id()?,
+                message as _,
+                x,
+                y,
+                flags.into()
+            ))
+        }?;
+        Ok(())
     }
 
     /// Fire mouse wheel event.
@@ -288,105 +845,269 @@ impl WebView {
         y: i32,
         flags: MouseFlags,
     ) {
+        self.try_fire_mouse_wheel_event(message, x, y, flags)
+            .unwrap_or_gone()
+    }
+
+    /// Fallible version of `fire_mouse_wheel_event`.
+    pub fn try_fire_mouse_wheel_event(
+        &self,
+        message: WindowMessage,
+        x: i32,
+        y: i32,
+        flags: MouseFlags,
+    ) -> MBResult<()> {
         unsafe {
-            call_api_or_panic().mbFireMouseWheelEvent(
-                self.as_id(),
+            mb_call!(mbFireMouseWheelEvent(
+                self.try_id()?,
                 message as _,
                 x,
                 y,
                 flags.into(),
-            )
-        };
+            ))
+        }?;
+        Ok(())
     }
 
     /// Fire key up event.
     pub fn fire_key_up_event(&self, key: VirtualKeyCode, flags: KeyboardFlags, system_key: bool) {
+        self.try_fire_key_up_event(key, flags, system_key)
+            .unwrap_or_gone()
+    }
+
+    /// Fallible version of `fire_key_up_event`.
+    pub fn try_fire_key_up_event(
+        &self,
+        key: VirtualKeyCode,
+        flags: KeyboardFlags,
+        system_key: bool,
+    ) -> MBResult<()> {
         unsafe {
-            call_api_or_panic().mbFireKeyUpEvent(
-                self.as_id(),
+            mb_call!(mbFireKeyUpEvent(
+                self.try_id()?,
                 key as _,
                 flags as _,
                 system_key as _,
-            );
-        }
+            ))
+        }?;
+        Ok(())
     }
 
     /// Fire key down event.
     pub fn fire_key_down_event(&self, key: VirtualKeyCode, flags: KeyboardFlags, system_key: bool) {
+        self.try_fire_key_down_event(key, flags, system_key)
+            .unwrap_or_gone()
+    }
+
+    /// Fallible version of `fire_key_down_event`.
+    pub fn try_fire_key_down_event(
+        &self,
+        key: VirtualKeyCode,
+        flags: KeyboardFlags,
+        system_key: bool,
+    ) -> MBResult<()> {
         unsafe {
-            call_api_or_panic().mbFireKeyDownEvent(
-                self.as_id(),
+            mb_call!(mbFireKeyDownEvent(
+                self.try_id()?,
                 key as _,
                 flags as _,
                 system_key as _,
-            );
-        }
+            ))
+        }?;
+        Ok(())
     }
 
     /// Fire key press event. Char code todo!
     pub fn fire_key_press_event(&self, char_code: u32, flags: KeyboardFlags, system_key: bool) {
+        self.try_fire_key_press_event(char_code, flags, system_key)
+            .unwrap_or_gone()
+    }
+
+    /// Fallible version of `fire_key_press_event`.
+    pub fn try_fire_key_press_event(
+        &self,
+        char_code: u32,
+        flags: KeyboardFlags,
+        system_key: bool,
+    ) -> MBResult<()> {
         unsafe {
-            call_api_or_panic().mbFireKeyPressEvent(
-                self.as_id(),
+            mb_call!(mbFireKeyPressEvent(
+                self.try_id()?,
                 char_code,
                 flags as _,
                 system_key as _,
-            );
-        }
+            ))
+        }?;
+        Ok(())
     }
 
     /// Set focus.
     pub fn set_focus(&self) {
-        unsafe {
-            call_api_or_panic().mbSetFocus(self.as_id());
-        }
+        self.try_set_focus().unwrap_or_gone()
+    }
+
+    /// Fallible version of `set_focus`.
+    pub fn try_set_focus(&self) -> MBResult<()> {
+        unsafe { mb_call!(mbSetFocus(self.try_id()?)) }
     }
 
     /// Kill focus.
     pub fn kill_focus(&self) {
-        unsafe {
-            call_api_or_panic().mbKillFocus(self.as_id());
-        }
+        self.try_kill_focus().unwrap_or_gone()
+    }
+
+    /// Fallible version of `kill_focus`.
+    pub fn try_kill_focus(&self) -> MBResult<()> {
+        unsafe { mb_call!(mbKillFocus(self.try_id()?)) }
+    }
+
+    /// Run `script` in every new main world script context, before the page scripts.
+    ///
+    /// The script runs in the main frame and the iframes, and again after each navigation or
+    /// reload. It does not run in documents which already exist. Call `unsubscribe` on the result
+    /// to stop running it.
+    ///
+    /// ```rust,no_run
+    /// use miniblink::{app, webview::WebView};
+    ///
+    /// app::init("./mb.dll").unwrap();
+    /// let view = WebView::default();
+    /// view.add_init_script("window.embedded = true;");
+    /// ```
+    pub fn add_init_script(&self, script: &str) -> Subscription {
+        self.try_add_init_script(script).unwrap_or_gone()
+    }
+
+    /// Fallible version of `add_init_script`.
+    ///
+    /// # Remarks
+    /// Returns `MBError::Unsupported` if the library lacks `mbOnDidCreateScriptContext`.
+    pub fn try_add_init_script(&self, script: &str) -> MBResult<Subscription> {
+        self.subscribe_init_script(i32::MAX - 2, script.to_string())
+    }
+
+    /// Run the script in every new main world context, ordered by `priority`.
+    pub(crate) fn subscribe_init_script(
+        &self,
+        priority: i32,
+        script: String,
+    ) -> MBResult<Subscription> {
+        to_cstring(&*script)?;
+        self.try_subscribe::<ScriptContextCreated, _>(priority, move |webview, params| {
+            if params.world_id == 0 {
+                let _ = webview.try_eval(params.frame, &script, false);
+            }
+        })
+    }
+
+    /// Run js and get the result.
+    pub fn eval(&self, frame_handle: WebFrameHandle, script: &str, is_in_closure: bool) -> JsValue {
+        self.try_eval(frame_handle, script, is_in_closure)
+            .unwrap_or_gone()
     }
 
-    /// Run js.
-    pub fn eval(&self, frame_handle: WebFrameHandle, script: &str, is_in_closure: bool) -> String {
-        let script = CString::new(script).unwrap();
-        let is_in_closure = if is_in_closure { 1 } else { 0 };
+    /// Fallible version of `eval`.
+    pub fn try_eval(
+        &self,
+        frame_handle: WebFrameHandle,
+        script: &str,
+        is_in_closure: bool,
+    ) -> MBResult<JsValue> {
+        let script = to_cstring(script)?;
         unsafe {
-            let result = call_api_or_panic().mbRunJsSync(
-                self.as_id(),
+            let result = mb_call!(mbRunJsSync(
+                self.try_id()?,
                 frame_handle.as_ptr(),
                 script.as_ptr(),
-                is_in_closure,
-            );
-
-            let es =
-                call_api_or_panic().mbGetGlobalExecByFrame(self.as_id(), frame_handle.as_ptr());
-            let result = call_api_or_panic().mbJsToString(es, result);
-            CStr::from_ptr(result).to_string_lossy().to_string()
+                is_in_closure as _,
+            ))?;
+
+            let es = mb_call!(mbGetGlobalExecByFrame(
+                self.try_id()?,
+                frame_handle.as_ptr()
+            ))?;
+            JsValue::from_raw(es, result)
         }
     }
 
-    /// Eval a script on the frame.
-    pub fn on_query<F>(&self, callback: F)
-    where
-        F: OnQuery,
-    {
-        use miniblink_sys::{mbJsExecState, mbWebView};
-        let context = self.store_callback_context(callback);
+    /// Run js without blocking.
+    pub fn eval_async(
+        &self,
+        frame_handle: WebFrameHandle,
+        script: &str,
+        is_in_closure: bool,
+    ) -> MBFuture<JsValue> {
+        let (future, completer) = MBFuture::pending();
+        let script = match to_cstring(script) {
+            Ok(script) => script,
+            Err(error) => return MBFuture::ready(Err(error)),
+        };
+        let id = match self.try_id() {
+            Ok(id) => id,
+            Err(error) => return MBFuture::ready(Err(error)),
+        };
+        let api = match crate::call_symbol("mbRunJs") {
+            Ok(api) => api,
+            Err(error) => return MBFuture::ready(Err(error)),
+        };
 
-        extern "system" fn on_query<F>(
-            _: mbWebView,
-            context: *mut c_void,
-            _es: mbJsExecState,
-            query_id: i64,
+        extern "system" fn shim(
+            _: WebViewID,
+            param: *mut c_void,
+            es: miniblink_sys::mbJsExecState,
+            value: miniblink_sys::mbJsValue,
+        ) {
+            let completer = unsafe { Box::from_raw(param as *mut Completer<JsValue>) };
+            completer.complete(unsafe { JsValue::from_raw(es, value) });
+        }
+
+        let param = Box::into_raw(Box::new(completer));
+        unsafe {
+            api.mbRunJs(
+                id,
+                frame_handle.as_ptr(),
+                script.as_ptr(),
+                is_in_closure as _,
+                Some(shim),
+                param as _,
+                std::ptr::null_mut(),
+            )
+        };
+        future
+    }
+
+    /// Eval a script on the frame.
+    pub fn on_query<F>(&self, callback: F) -> CallbackHandle
+    where
+        F: OnQuery,
+    {
+        self.try_on_query(callback).unwrap_or_gone()
+    }
+
+    /// Fallible version of `on_query`.
+    pub fn try_on_query<F>(&self, callback: F) -> MBResult<CallbackHandle>
+    where
+        F: OnQuery,
+    {
+        use miniblink_sys::{mbJsExecState, mbWebView};
+        let api = crate::call_symbol("mbOnJsQuery")?;
+        let id = self.try_id()?;
+        let (context, pending) =
+            self.store_callback_context("mbOnJsQuery", callback, |id| unsafe {
+                let _ = mb_call!(mbOnJsQuery(id, None, std::ptr::null_mut()));
+            });
+
+        extern "system" fn on_query<F>(
+            _: mbWebView,
+            context: *mut c_void,
+            es: mbJsExecState,
+            query_id: i64,
             custom_msg: c_int,
             request: *const i8,
         ) where
             F: OnQuery,
         {
-            let context = unsafe { &*(context as *const CallBackContext<F>) };
+            let context = unsafe { CallBackContext::<F>::from_param(context) };
 
             let Some(inner) = context.webview.upgrade() else {
                 return;
@@ -397,51 +1118,409 @@ impl WebView {
             let query_params = JsQueryParameters {
                 custom_message: custom_msg,
                 request,
+                frame: crate::frame::find_by_exec(&webview, es),
             };
 
             if let Ok(result) = catch_unwind(AssertUnwindSafe(|| {
                 (context.content)(&webview, &query_params)
             })) {
-                let response = CString::new(result.response).unwrap();
-                unsafe {
-                    call_api_or_panic().mbResponseQuery(
-                        webview.as_id(),
-                        query_id,
-                        result.custom_message,
-                        response.as_ptr(),
-                    )
-                };
+                let response = to_cstring(result.response).unwrap_or_default();
+                if let Ok(api) = call_backend() {
+                    unsafe {
+                        api.mbResponseQuery(
+                            webview.as_id(),
+                            query_id,
+                            result.custom_message,
+                            response.as_ptr(),
+                        )
+                    };
+                }
             }
         }
 
-        unsafe { call_api_or_panic().mbOnJsQuery(self.as_id(), Some(on_query::<F>), context as _) }
+        unsafe { api.mbOnJsQuery(id, Some(on_query::<F>), context as _) };
+        Ok(pending.registered())
+    }
+
+    /// Handle the queries with `custom_message` equal to `channel_id`.
+    ///
+    /// The handler receives the request and returns the response, which is sent with the same
+    /// `custom_message`. An error is sent as `{"message": "..."}` with `QUERY_ERROR`. Each channel
+    /// has one handler, and routing a channel again replaces its handler. Negative channel ids are
+    /// reserved, and return `MBError::InvalidChannel`.
+    ///
+    /// # Remarks
+    /// The handlers share the `on_query` callback. Calling `on_query` directly replaces them until
+    /// the next `route_query`.
+    pub fn route_query<F>(&self, channel_id: i32, handler: F)
+    where
+        F: Fn(&WebView, &str) -> Result<String, String> + Send + 'static,
+    {
+        self.try_route_query(channel_id, handler).unwrap_or_gone()
+    }
+
+    /// Fallible version of `route_query`.
+    pub fn try_route_query<F>(&self, channel_id: i32, handler: F) -> MBResult<()>
+    where
+        F: Fn(&WebView, &str) -> Result<String, String> + Send + 'static,
+    {
+        crate::query::route(self, channel_id, None, handler)
+    }
+
+    /// Remove the handler of `channel_id` set by `route_query` or `handle_query`.
+    pub fn remove_query_handler(&self, channel_id: i32) {
+        crate::query::unroute(self, channel_id, None)
+    }
+
+    /// Handle the queries of `channel_id` with a typed handler, see `route_query`.
+    ///
+    /// The request is deserialized from JSON and the response is serialized to JSON.
+    ///
+    /// ```rust,no_run
+    /// use miniblink::{app, webview::WebView};
+    ///
+    /// #[derive(serde::Deserialize)]
+    /// struct Add {
+    ///     a: i32,
+    ///     b: i32,
+    /// }
+    ///
+    /// app::init("./mb.dll").unwrap();
+    /// let view = WebView::default();
+    /// // window.mbQuery(1, JSON.stringify({a: 1, b: 2}), (msg, sum) => console.log(sum));
+    /// view.handle_query(1, |_, req: Add| req.a.checked_add(req.b).ok_or("overflow"));
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn handle_query<Req, Resp, E>(
+        &self,
+        channel_id: i32,
+        handler: impl Fn(&WebView, Req) -> Result<Resp, E> + Send + 'static,
+    ) where
+        Req: serde::de::DeserializeOwned,
+        Resp: serde::Serialize,
+        E: std::fmt::Display,
+    {
+        self.try_handle_query(channel_id, handler).unwrap_or_gone()
+    }
+
+    /// Fallible version of `handle_query`.
+    #[cfg(feature = "serde")]
+    pub fn try_handle_query<Req, Resp, E>(
+        &self,
+        channel_id: i32,
+        handler: impl Fn(&WebView, Req) -> Result<Resp, E> + Send + 'static,
+    ) -> MBResult<()>
+    where
+        Req: serde::de::DeserializeOwned,
+        Resp: serde::Serialize,
+        E: std::fmt::Display,
+    {
+        crate::query::route(self, channel_id, None, crate::query::json_handler(handler))
+    }
+
+    /// Register a command which JavaScript calls with `window.rust.invoke(name, args)`.
+    ///
+    /// The handler receives the arguments as JSON and returns the result as JSON. The promise
+    /// returned by `invoke` resolves with the result, or rejects with an `Error` carrying the
+    /// message of `Err`. Registering a name again replaces its handler.
+    ///
+    /// # Remarks
+    /// The `window.rust` prelude is injected into every frame as an init script, see
+    /// `add_init_script`, and the calls are routed through `route_query` with `COMMAND_QUERY`.
+    /// Older libraries lacking the script context callbacks inject it on `events::DocumentReady`.
+    ///
+    /// ```rust,no_run
+    /// use miniblink::{app, webview::WebView};
+    ///
+    /// app::init("./mb.dll").unwrap();
+    /// let view = WebView::default();
+    /// // await window.rust.invoke("greet", "world")
+    /// view.command("greet", |_, args| Ok(format!("\"hello, {}\"", args.trim_matches('"'))));
+    /// ```
+    pub fn command<F>(&self, name: &str, handler: F)
+    where
+        F: Fn(&WebView, &str) -> Result<String, String> + Send + 'static,
+    {
+        self.try_command(name, handler).unwrap_or_gone()
+    }
+
+    /// Fallible version of `command`.
+    ///
+    /// # Remarks
+    /// Returns `MBError::InvalidString` if the name is empty or contains a newline.
+    pub fn try_command<F>(&self, name: &str, handler: F) -> MBResult<()>
+    where
+        F: Fn(&WebView, &str) -> Result<String, String> + Send + 'static,
+    {
+        crate::command::register(self, name, handler)
+    }
+
+    /// Remove the command registered by `command` or `handle_command`.
+    pub fn remove_command(&self, name: &str) {
+        crate::command::unregister(self, name)
+    }
+
+    /// Register a command with typed arguments and result, see `command`.
+    ///
+    /// ```rust,no_run
+    /// use miniblink::{app, webview::WebView};
+    ///
+    /// app::init("./mb.dll").unwrap();
+    /// let view = WebView::default();
+    /// // await window.rust.invoke("read_file", "Cargo.toml")
+    /// view.handle_command("read_file", |_, path: String| std::fs::read_to_string(path));
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn handle_command<Args, Resp, E, F>(&self, name: &str, handler: F)
+    where
+        Args: serde::de::DeserializeOwned,
+        Resp: serde::Serialize,
+        E: std::fmt::Display,
+        F: Fn(&WebView, Args) -> Result<Resp, E> + Send + 'static,
+    {
+        self.try_handle_command(name, handler).unwrap_or_gone()
+    }
+
+    /// Fallible version of `handle_command`.
+    #[cfg(feature = "serde")]
+    pub fn try_handle_command<Args, Resp, E, F>(&self, name: &str, handler: F) -> MBResult<()>
+    where
+        Args: serde::de::DeserializeOwned,
+        Resp: serde::Serialize,
+        E: std::fmt::Display,
+        F: Fn(&WebView, Args) -> Result<Resp, E> + Send + 'static,
+    {
+        crate::command::register(self, name, crate::query::json_handler(handler))
+    }
+
+    /// Dispatch a `CustomEvent` named `event` on the window of the main frame.
+    ///
+    /// The payload is the `detail` of the event. Strings are escaped, so any text is delivered
+    /// as is. With the `serde` feature, wrap a `Serialize` value in `types::Json` to send it as an
+    /// object.
+    ///
+    /// # Remarks
+    /// If the page is still loading, the event is queued until the document is ready. A
+    /// navigation canceled by a listener of `events::Navigation` keeps the current document.
+    ///
+    /// ```rust,no_run
+    /// use miniblink::{app, webview::WebView};
+    ///
+    /// app::init("./mb.dll").unwrap();
+    /// let view = WebView::default();
+    /// // window.addEventListener("progress", (e) => console.log(e.detail));
+    /// view.emit("progress", "50%");
+    /// ```
+    pub fn emit<P>(&self, event: &str, payload: &P)
+    where
+        P: EmitPayload + ?Sized,
+    {
+        self.try_emit(event, payload).unwrap_or_gone()
+    }
+
+    /// Fallible version of `emit`.
+    pub fn try_emit<P>(&self, event: &str, payload: &P) -> MBResult<()>
+    where
+        P: EmitPayload + ?Sized,
+    {
+        crate::emit::emit(self, None, event, payload)
+    }
+
+    /// Dispatch a `CustomEvent` on the window of `frame`, see `emit`.
+    pub fn emit_to_frame<P>(&self, frame: WebFrameHandle, event: &str, payload: &P)
+    where
+        P: EmitPayload + ?Sized,
+    {
+        self.try_emit_to_frame(frame, event, payload)
+            .unwrap_or_gone()
+    }
+
+    /// Fallible version of `emit_to_frame`.
+    pub fn try_emit_to_frame<P>(
+        &self,
+        frame: WebFrameHandle,
+        event: &str,
+        payload: &P,
+    ) -> MBResult<()>
+    where
+        P: EmitPayload + ?Sized,
+    {
+        crate::emit::emit(self, Some(frame), event, payload)
     }
 
     /// Set zoom factor.
     pub fn set_zoom_factor(&self, factor: f32) {
-        unsafe {
-            call_api_or_panic().mbSetZoomFactor(self.as_id(), factor);
-        }
+        self.try_set_zoom_factor(factor).unwrap_or_gone()
+    }
+
+    /// Fallible version of `set_zoom_factor`.
+    pub fn try_set_zoom_factor(&self, factor: f32) -> MBResult<()> {
+        unsafe { mb_call!(mbSetZoomFactor(self.try_id()?, factor)) }
     }
 
     /// Get zoom factor.
     pub fn get_zoom_factor(&self) -> f32 {
-        let id = self.as_id();
-        invoke_command_sync(move || unsafe { call_api_or_panic().mbGetZoomFactor(id) })
+        self.try_get_zoom_factor().unwrap_or_gone()
+    }
+
+    /// Fallible version of `get_zoom_factor`.
+    pub fn try_get_zoom_factor(&self) -> MBResult<f32> {
+        let id = self.try_id()?;
+        invoke_command_sync(move || unsafe { mb_call!(mbGetZoomFactor(id)) })?
+    }
+
+    /// Get the url of the main frame.
+    pub fn url(&self) -> String {
+        self.try_url().unwrap_or_gone()
+    }
+
+    /// Fallible version of `url`.
+    pub fn try_url(&self) -> MBResult<String> {
+        let id = self.try_id()?;
+        invoke_command_sync(move || unsafe { mb_call!(mbGetUrl(id)).map(|x| from_utf8_ptr(x)) })?
+    }
+
+    /// Get the document title.
+    pub fn title(&self) -> String {
+        self.try_title().unwrap_or_gone()
+    }
+
+    /// Fallible version of `title`.
+    pub fn try_title(&self) -> MBResult<String> {
+        let id = self.try_id()?;
+        invoke_command_sync(move || unsafe { mb_call!(mbGetTitle(id)).map(|x| from_utf8_ptr(x)) })?
+    }
+
+    /// Check if the page is loading.
+    pub fn is_loading(&self) -> bool {
+        self.try_is_loading().unwrap_or_gone()
+    }
+
+    /// Fallible version of `is_loading`.
+    pub fn try_is_loading(&self) -> MBResult<bool> {
+        let id = self.try_id()?;
+        invoke_command_sync(move || unsafe { mb_call!(mbIsLoading(id)).map(|x| x != 0) })?
+    }
+
+    /// Get the size of the document, which may be larger than the webview.
+    ///
+    /// ```rust,no_run
+    /// use miniblink::{app, webview::WebView};
+    ///
+    /// app::init("./mb.dll").unwrap();
+    /// let view = WebView::default();
+    /// let size = view.content_size();
+    /// println!("{}x{}", size.width, size.height);
+    /// ```
+    pub fn content_size(&self) -> Size {
+        self.try_content_size().unwrap_or_gone()
+    }
+
+    /// Fallible version of `content_size`.
+    pub fn try_content_size(&self) -> MBResult<Size> {
+        let id = self.try_id()?;
+        invoke_command_sync(move || unsafe {
+            Ok(Size {
+                width: mb_call!(mbGetContentWidth(id))?,
+                height: mb_call!(mbGetContentHeight(id))?,
+            })
+        })?
+    }
+
+    /// Get the size of the webview.
+    pub fn size(&self) -> Size {
+        self.try_size().unwrap_or_gone()
+    }
+
+    /// Fallible version of `size`.
+    pub fn try_size(&self) -> MBResult<Size> {
+        let id = self.try_id()?;
+        invoke_command_sync(move || unsafe {
+            let mut rect = std::mem::zeroed();
+            mb_call!(mbGetSize(id, &mut rect))?;
+            Ok(Rect::from_mb(rect).size())
+        })?
+    }
+
+    /// Get the caret of the focused editable element, relative to the webview. Used to place the
+    /// IME candidate window.
+    pub fn caret_rect(&self) -> Rect {
+        self.try_caret_rect().unwrap_or_gone()
+    }
+
+    /// Fallible version of `caret_rect`.
+    pub fn try_caret_rect(&self) -> MBResult<Rect> {
+        let id = self.try_id()?;
+        invoke_command_sync(move || unsafe {
+            let mut rect = std::mem::zeroed();
+            mb_call!(mbGetCaretRect(id, &mut rect))?;
+            Ok(Rect::from_mb(rect))
+        })?
+    }
+
+    /// Get the cursor shown over the page.
+    pub fn cursor_type(&self) -> CursorType {
+        self.try_cursor_type().unwrap_or_gone()
+    }
+
+    /// Fallible version of `cursor_type`.
+    ///
+    /// # Remarks
+    /// Returns `MBError::UndefinedEnumTransmute` if miniblink reports an unknown cursor.
+    pub fn try_cursor_type(&self) -> MBResult<CursorType> {
+        let id = self.try_id()?;
+        let cursor = invoke_command_sync(move || unsafe { mb_call!(mbGetCursorInfoType(id)) })??;
+        CursorType::from_mb(cursor).ok_or(MBError::UndefinedEnumTransmute)
+    }
+
+    /// Get the window rect on the screen. Returns `None` if the webview has no window.
+    pub fn window_rect(&self) -> Option<Rect> {
+        self.try_window_rect().unwrap_or_gone()
+    }
+
+    /// Fallible version of `window_rect`.
+    pub fn try_window_rect(&self) -> MBResult<Option<Rect>> {
+        let id = self.try_id()?;
+        invoke_command_sync(move || unsafe {
+            let mut rect = std::mem::zeroed();
+            let ok = mb_call!(mbGetWindowRect(id, &mut rect))? != 0;
+            Ok(ok.then(|| Rect::from_mb(rect)))
+        })?
     }
 
     /// Set title changed callback.
-    pub fn on_title_changed<F>(&self, callback: F)
+    pub fn on_title_changed<F>(&self, callback: F) -> CallbackHandle
+    where
+        F: OnTitleChanged,
+    {
+        self.try_on_title_changed(callback).unwrap_or_gone()
+    }
+
+    /// Fallible version of `on_title_changed`.
+    pub fn try_on_title_changed<F>(&self, callback: F) -> MBResult<CallbackHandle>
+    where
+        F: OnTitleChanged,
+    {
+        events::set_direct::<events::TitleChanged, _>(self, callback)
+    }
+
+    /// Install the native callback of `events::TitleChanged`.
+    pub(crate) fn install_on_title_changed<F>(&self, callback: F) -> MBResult<CallbackHandle>
     where
         F: OnTitleChanged,
     {
-        let context = self.store_callback_context(callback);
+        let api = crate::call_symbol("mbOnTitleChanged")?;
+        let id = self.try_id()?;
+        let (context, pending) =
+            self.store_callback_context("mbOnTitleChanged", callback, |id| unsafe {
+                let _ = mb_call!(mbOnTitleChanged(id, None, std::ptr::null_mut()));
+            });
 
         extern "system" fn shim<F>(_: WebViewID, context: *mut c_void, title: *const c_char)
         where
             F: OnTitleChanged,
         {
-            let context = unsafe { &*(context as *const CallBackContext<F>) };
+            let context = unsafe { CallBackContext::<F>::from_param(context) };
             let Some(inner) = context.webview.upgrade() else {
                 return;
             };
@@ -451,17 +1530,37 @@ impl WebView {
             let _ = catch_unwind(AssertUnwindSafe(|| (context.content)(&webview, &title)));
         }
 
-        unsafe {
-            call_api_or_panic().mbOnTitleChanged(self.as_id(), Some(shim::<F>), context as _);
-        }
+        unsafe { api.mbOnTitleChanged(id, Some(shim::<F>), context as _) };
+        Ok(pending.registered())
     }
 
     /// Set URL changed callback.
-    pub fn on_url_changed<F>(&self, callback: F)
+    pub fn on_url_changed<F>(&self, callback: F) -> CallbackHandle
+    where
+        F: OnUrlChanged,
+    {
+        self.try_on_url_changed(callback).unwrap_or_gone()
+    }
+
+    /// Fallible version of `on_url_changed`.
+    pub fn try_on_url_changed<F>(&self, callback: F) -> MBResult<CallbackHandle>
     where
         F: OnUrlChanged,
     {
-        let context = self.store_callback_context(callback);
+        events::set_direct::<events::UrlChanged, _>(self, callback)
+    }
+
+    /// Install the native callback of `events::UrlChanged`.
+    pub(crate) fn install_on_url_changed<F>(&self, callback: F) -> MBResult<CallbackHandle>
+    where
+        F: OnUrlChanged,
+    {
+        let api = crate::call_symbol("mbOnURLChanged")?;
+        let id = self.try_id()?;
+        let (context, pending) =
+            self.store_callback_context("mbOnURLChanged", callback, |id| unsafe {
+                let _ = mb_call!(mbOnURLChanged(id, None, std::ptr::null_mut()));
+            });
 
         extern "system" fn shim<F>(
             _: WebViewID,
@@ -472,7 +1571,7 @@ impl WebView {
         ) where
             F: OnUrlChanged,
         {
-            let context = unsafe { &*(context as *const CallBackContext<F>) };
+            let context = unsafe { CallBackContext::<F>::from_param(context) };
             let Some(inner) = context.webview.upgrade() else {
                 return;
             };
@@ -487,23 +1586,35 @@ impl WebView {
             let _ = catch_unwind(AssertUnwindSafe(|| (context.content)(&webview, &param)));
         }
 
-        unsafe {
-            call_api_or_panic().mbOnURLChanged(self.as_id(), Some(shim::<F>), context as _);
-        }
+        unsafe { api.mbOnURLChanged(id, Some(shim::<F>), context as _) };
+        Ok(pending.registered())
     }
 
     /// Set alert box callback.
-    pub fn on_alert_box<F>(&self, callback: F)
+    pub fn on_alert_box<F>(&self, callback: F) -> CallbackHandle
     where
         F: OnAlertBox,
     {
-        let context = self.store_callback_context(callback);
+        self.try_on_alert_box(callback).unwrap_or_gone()
+    }
+
+    /// Fallible version of `on_alert_box`.
+    pub fn try_on_alert_box<F>(&self, callback: F) -> MBResult<CallbackHandle>
+    where
+        F: OnAlertBox,
+    {
+        let api = crate::call_symbol("mbOnAlertBox")?;
+        let id = self.try_id()?;
+        let (context, pending) =
+            self.store_callback_context("mbOnAlertBox", callback, |id| unsafe {
+                let _ = mb_call!(mbOnAlertBox(id, None, std::ptr::null_mut()));
+            });
 
         extern "system" fn shim<F>(_: WebViewID, context: *mut c_void, message: *const c_char)
         where
             F: OnAlertBox,
         {
-            let context = unsafe { &*(context as *const CallBackContext<F>) };
+            let context = unsafe { CallBackContext::<F>::from_param(context) };
             let Some(inner) = context.webview.upgrade() else {
                 return;
             };
@@ -513,17 +1624,29 @@ impl WebView {
             let _ = catch_unwind(AssertUnwindSafe(|| (context.content)(&webview, &message)));
         }
 
-        unsafe {
-            call_api_or_panic().mbOnAlertBox(self.as_id(), Some(shim::<F>), context as _);
-        }
+        unsafe { api.mbOnAlertBox(id, Some(shim::<F>), context as _) };
+        Ok(pending.registered())
     }
 
     /// Set confirm box callback.
-    pub fn on_confirm_box<F>(&self, callback: F)
+    pub fn on_confirm_box<F>(&self, callback: F) -> CallbackHandle
     where
         F: OnConfirmBox,
     {
-        let context = self.store_callback_context(callback);
+        self.try_on_confirm_box(callback).unwrap_or_gone()
+    }
+
+    /// Fallible version of `on_confirm_box`.
+    pub fn try_on_confirm_box<F>(&self, callback: F) -> MBResult<CallbackHandle>
+    where
+        F: OnConfirmBox,
+    {
+        let api = crate::call_symbol("mbOnConfirmBox")?;
+        let id = self.try_id()?;
+        let (context, pending) =
+            self.store_callback_context("mbOnConfirmBox", callback, |id| unsafe {
+                let _ = mb_call!(mbOnConfirmBox(id, None, std::ptr::null_mut()));
+            });
 
         extern "system" fn shim<F>(
             _: WebViewID,
@@ -533,7 +1656,7 @@ impl WebView {
         where
             F: OnConfirmBox,
         {
-            let context = unsafe { &*(context as *const CallBackContext<F>) };
+            let context = unsafe { CallBackContext::<F>::from_param(context) };
             let Some(inner) = context.webview.upgrade() else {
                 return 0;
             };
@@ -546,17 +1669,29 @@ impl WebView {
             }
         }
 
-        unsafe {
-            call_api_or_panic().mbOnConfirmBox(self.as_id(), Some(shim::<F>), context as _);
-        }
+        unsafe { api.mbOnConfirmBox(id, Some(shim::<F>), context as _) };
+        Ok(pending.registered())
     }
 
     /// Set prompt box callback.
-    pub fn on_prompt_box<F>(&self, callback: F)
+    pub fn on_prompt_box<F>(&self, callback: F) -> CallbackHandle
+    where
+        F: OnPromptBox,
+    {
+        self.try_on_prompt_box(callback).unwrap_or_gone()
+    }
+
+    /// Fallible version of `on_prompt_box`.
+    pub fn try_on_prompt_box<F>(&self, callback: F) -> MBResult<CallbackHandle>
     where
         F: OnPromptBox,
     {
-        let context = self.store_callback_context(callback);
+        let api = crate::call_symbol("mbOnPromptBox")?;
+        let id = self.try_id()?;
+        let (context, pending) =
+            self.store_callback_context("mbOnPromptBox", callback, |id| unsafe {
+                let _ = mb_call!(mbOnPromptBox(id, None, std::ptr::null_mut()));
+            });
 
         extern "system" fn shim<F>(
             _: WebViewID,
@@ -568,7 +1703,7 @@ impl WebView {
         where
             F: OnPromptBox,
         {
-            let context = unsafe { &*(context as *const CallBackContext<F>) };
+            let context = unsafe { CallBackContext::<F>::from_param(context) };
             let Some(inner) = context.webview.upgrade() else {
                 unsafe { *reject = 0 }
                 return std::ptr::null_mut();
@@ -582,12 +1717,13 @@ impl WebView {
                 message,
                 default_value,
             };
-            match catch_unwind(AssertUnwindSafe(|| {
+            let result = catch_unwind(AssertUnwindSafe(|| {
                 (context.content)(&webview, &prompt_params)
-            })) {
-                Ok(Some(result)) => {
+            }));
+            match result.map(|x| x.map(MbString::new)) {
+                Ok(Some(Ok(result))) => {
                     unsafe { *reject = 1 };
-                    MbString::new(result).unwrap().into_raw()
+                    result.into_raw()
                 }
                 _ => {
                     unsafe { *reject = 0 };
@@ -596,19 +1732,39 @@ impl WebView {
             }
         }
 
-        unsafe {
-            call_api_or_panic().mbOnPromptBox(self.as_id(), Some(shim::<F>), context as _);
-        }
+        unsafe { api.mbOnPromptBox(id, Some(shim::<F>), context as _) };
+        Ok(pending.registered())
     }
 
     /// Set navigation callback.
     ///
     /// Returns true to continue navigation, false to cancel navigation.
-    pub fn on_navigation<F>(&self, callback: F)
+    pub fn on_navigation<F>(&self, callback: F) -> CallbackHandle
+    where
+        F: OnNavigation,
+    {
+        self.try_on_navigation(callback).unwrap_or_gone()
+    }
+
+    /// Fallible version of `on_navigation`.
+    pub fn try_on_navigation<F>(&self, callback: F) -> MBResult<CallbackHandle>
     where
         F: OnNavigation,
     {
-        let context = self.store_callback_context(callback);
+        events::set_direct::<events::Navigation, _>(self, callback)
+    }
+
+    /// Install the native callback of `events::Navigation`.
+    pub(crate) fn install_on_navigation<F>(&self, callback: F) -> MBResult<CallbackHandle>
+    where
+        F: OnNavigation,
+    {
+        let api = crate::call_symbol("mbOnNavigation")?;
+        let id = self.try_id()?;
+        let (context, pending) =
+            self.store_callback_context("mbOnNavigation", callback, |id| unsafe {
+                let _ = mb_call!(mbOnNavigation(id, None, std::ptr::null_mut()));
+            });
 
         extern "system" fn shim<F>(
             _: WebViewID,
@@ -619,7 +1775,7 @@ impl WebView {
         where
             F: OnNavigation,
         {
-            let context = unsafe { &*(context as *const CallBackContext<F>) };
+            let context = unsafe { CallBackContext::<F>::from_param(context) };
             let Some(inner) = context.webview.upgrade() else {
                 return 1;
             };
@@ -627,7 +1783,9 @@ impl WebView {
             let webview = WebView { inner };
             let url = unsafe { CStr::from_ptr(url).to_string_lossy().to_string() };
             let param = NavigationParameters {
-                navigation_type: unsafe { std::mem::transmute(navigation_type) },
+                navigation_type: unsafe {
+                    std::mem::transmute::<c_int, NavigationType>(navigation_type)
+                },
                 url,
             };
 
@@ -640,20 +1798,32 @@ impl WebView {
             }
         }
 
-        unsafe {
-            call_api_or_panic().mbOnNavigation(self.as_id(), Some(shim::<F>), context as _);
-        }
+        unsafe { api.mbOnNavigation(id, Some(shim::<F>), context as _) };
+        Ok(pending.registered())
     }
 
     /// Set create view callback.
     ///
     /// Invoked when a new webview is created after \<a\> link click.
-    pub fn on_create_view<F>(&self, callback: F)
+    pub fn on_create_view<F>(&self, callback: F) -> CallbackHandle
+    where
+        F: OnCreateView,
+    {
+        self.try_on_create_view(callback).unwrap_or_gone()
+    }
+
+    /// Fallible version of `on_create_view`.
+    pub fn try_on_create_view<F>(&self, callback: F) -> MBResult<CallbackHandle>
     where
         F: OnCreateView,
     {
         use miniblink_sys::mbWindowFeatures;
-        let context = self.store_callback_context(callback);
+        let api = crate::call_symbol("mbOnCreateView")?;
+        let id = self.try_id()?;
+        let (context, pending) =
+            self.store_callback_context("mbOnCreateView", callback, |id| unsafe {
+                let _ = mb_call!(mbOnCreateView(id, None, std::ptr::null_mut()));
+            });
 
         extern "system" fn shim<F>(
             _: WebViewID,
@@ -665,14 +1835,15 @@ impl WebView {
         where
             F: OnCreateView,
         {
-            let context = unsafe { &*(context as *const CallBackContext<F>) };
+            let context = unsafe { CallBackContext::<F>::from_param(context) };
             let Some(inner) = context.webview.upgrade() else {
                 return 1;
             };
 
             let webview = WebView { inner };
             let url = unsafe { CStr::from_ptr(url).to_string_lossy().to_string() };
-            let navigation_type = unsafe { std::mem::transmute(navigation_type) };
+            let navigation_type =
+                unsafe { std::mem::transmute::<c_int, NavigationType>(navigation_type) };
             let window_features =
                 WindowFeatures::from_mb_window_features(&unsafe { *window_features });
             let params = CreateViewParameters {
@@ -686,140 +1857,766 @@ impl WebView {
             }
         }
 
-        unsafe {
-            call_api_or_panic().mbOnCreateView(self.as_id(), Some(shim::<F>), context as _);
-        }
+        unsafe { api.mbOnCreateView(id, Some(shim::<F>), context as _) };
+        Ok(pending.registered())
     }
 
     /// Set a callback when the page DOM emits a ready event. It is possible to determine whether it is the main frame or not.
-    pub fn on_document_ready<F>(&self, callback: F)
+    pub fn on_document_ready<F>(&self, callback: F) -> CallbackHandle
+    where
+        F: OnDocumentReady,
+    {
+        self.try_on_document_ready(callback).unwrap_or_gone()
+    }
+
+    /// Fallible version of `on_document_ready`.
+    pub fn try_on_document_ready<F>(&self, callback: F) -> MBResult<CallbackHandle>
     where
         F: OnDocumentReady,
     {
-        let context = self.store_callback_context(callback);
+        events::set_direct::<events::DocumentReady, _>(self, callback)
+    }
+
+    /// Install the native callback of `events::DocumentReady`.
+    pub(crate) fn install_on_document_ready<F>(&self, callback: F) -> MBResult<CallbackHandle>
+    where
+        F: OnDocumentReady,
+    {
+        let api = crate::call_symbol("mbOnDocumentReady")?;
+        let id = self.try_id()?;
+        let (context, pending) =
+            self.store_callback_context("mbOnDocumentReady", callback, |id| unsafe {
+                let _ = mb_call!(mbOnDocumentReady(id, None, std::ptr::null_mut()));
+            });
 
         extern "system" fn shim<F>(_: WebViewID, context: *mut c_void, frame_id: *mut c_void)
         where
             F: OnDocumentReady,
         {
-            let context = unsafe { &*(context as *const CallBackContext<F>) };
+            let context = unsafe { CallBackContext::<F>::from_param(context) };
+            let Some(inner) = context.webview.upgrade() else {
+                return;
+            };
+
+            let webview = WebView { inner };
+            let frame_id = WebFrameHandle { inner: frame_id };
+
+            let _ = catch_unwind(AssertUnwindSafe(|| (context.content)(&webview, &frame_id)));
+        }
+
+        unsafe { api.mbOnDocumentReady(id, Some(shim::<F>), context as _) };
+        Ok(pending.registered())
+    }
+
+    /// Set a callback when a script context is created, before the page scripts run.
+    ///
+    /// Each frame has a context per world. The main world, with `world_id` zero, runs the page
+    /// scripts. See `add_init_script` to run a script in every new main world context.
+    pub fn on_did_create_script_context<F>(&self, callback: F) -> CallbackHandle
+    where
+        F: OnDidCreateScriptContext,
+    {
+        self.try_on_did_create_script_context(callback)
+            .unwrap_or_gone()
+    }
+
+    /// Fallible version of `on_did_create_script_context`.
+    pub fn try_on_did_create_script_context<F>(&self, callback: F) -> MBResult<CallbackHandle>
+    where
+        F: OnDidCreateScriptContext,
+    {
+        events::set_direct::<events::ScriptContextCreated, _>(self, callback)
+    }
+
+    /// Install the native callback of `events::ScriptContextCreated`.
+    pub(crate) fn install_on_did_create_script_context<F>(
+        &self,
+        callback: F,
+    ) -> MBResult<CallbackHandle>
+    where
+        F: OnDidCreateScriptContext,
+    {
+        let api = crate::call_symbol("mbOnDidCreateScriptContext")?;
+        let id = self.try_id()?;
+        let (context, pending) =
+            self.store_callback_context("mbOnDidCreateScriptContext", callback, |id| unsafe {
+                let _ = mb_call!(mbOnDidCreateScriptContext(id, None, std::ptr::null_mut()));
+            });
+
+        extern "system" fn shim<F>(
+            _: WebViewID,
+            context: *mut c_void,
+            frame_id: *mut c_void,
+            _: *mut c_void,
+            extension_group: c_int,
+            world_id: c_int,
+        ) where
+            F: OnDidCreateScriptContext,
+        {
+            let context = unsafe { CallBackContext::<F>::from_param(context) };
+            let Some(inner) = context.webview.upgrade() else {
+                return;
+            };
+
+            let webview = WebView { inner };
+            let params = ScriptContextParameters {
+                frame: WebFrameHandle { inner: frame_id },
+                extension_group,
+                world_id,
+            };
+
+            let _ = catch_unwind(AssertUnwindSafe(|| (context.content)(&webview, &params)));
+        }
+
+        unsafe { api.mbOnDidCreateScriptContext(id, Some(shim::<F>), context as _) };
+        Ok(pending.registered())
+    }
+
+    /// Set a callback when a script context is about to be released, for example when its frame
+    /// navigates or is removed.
+    pub fn on_will_release_script_context<F>(&self, callback: F) -> CallbackHandle
+    where
+        F: OnWillReleaseScriptContext,
+    {
+        self.try_on_will_release_script_context(callback)
+            .unwrap_or_gone()
+    }
+
+    /// Fallible version of `on_will_release_script_context`.
+    pub fn try_on_will_release_script_context<F>(&self, callback: F) -> MBResult<CallbackHandle>
+    where
+        F: OnWillReleaseScriptContext,
+    {
+        events::set_direct::<events::ScriptContextReleased, _>(self, callback)
+    }
+
+    /// Install the native callback of `events::ScriptContextReleased`.
+    pub(crate) fn install_on_will_release_script_context<F>(
+        &self,
+        callback: F,
+    ) -> MBResult<CallbackHandle>
+    where
+        F: OnWillReleaseScriptContext,
+    {
+        let api = crate::call_symbol("mbOnWillReleaseScriptContext")?;
+        let id = self.try_id()?;
+        let (context, pending) =
+            self.store_callback_context("mbOnWillReleaseScriptContext", callback, |id| unsafe {
+                let _ = mb_call!(mbOnWillReleaseScriptContext(id, None, std::ptr::null_mut()));
+            });
+
+        extern "system" fn shim<F>(
+            _: WebViewID,
+            context: *mut c_void,
+            frame_id: *mut c_void,
+            _: *mut c_void,
+            world_id: c_int,
+        ) where
+            F: OnWillReleaseScriptContext,
+        {
+            let context = unsafe { CallBackContext::<F>::from_param(context) };
             let Some(inner) = context.webview.upgrade() else {
                 return;
             };
 
+            let webview = WebView { inner };
+            let params = ScriptContextParameters {
+                frame: WebFrameHandle { inner: frame_id },
+                extension_group: 0,
+                world_id,
+            };
+
+            let _ = catch_unwind(AssertUnwindSafe(|| (context.content)(&webview, &params)));
+        }
+
+        unsafe { api.mbOnWillReleaseScriptContext(id, Some(shim::<F>), context as _) };
+        Ok(pending.registered())
+    }
+
+    /// Set a callback when a frame finished loading, failed or is canceled.
+    pub fn on_loading_finish<F>(&self, callback: F) -> CallbackHandle
+    where
+        F: OnLoadingFinish,
+    {
+        self.try_on_loading_finish(callback).unwrap_or_gone()
+    }
+
+    /// Fallible version of `on_loading_finish`.
+    pub fn try_on_loading_finish<F>(&self, callback: F) -> MBResult<CallbackHandle>
+    where
+        F: OnLoadingFinish,
+    {
+        events::set_direct::<events::LoadingFinish, _>(self, callback)
+    }
+
+    /// Install the native callback of `events::LoadingFinish`.
+    pub(crate) fn install_on_loading_finish<F>(&self, callback: F) -> MBResult<CallbackHandle>
+    where
+        F: OnLoadingFinish,
+    {
+        let api = crate::call_symbol("mbOnLoadingFinish")?;
+        let id = self.try_id()?;
+        let (context, pending) =
+            self.store_callback_context("mbOnLoadingFinish", callback, |id| unsafe {
+                let _ = mb_call!(mbOnLoadingFinish(id, None, std::ptr::null_mut()));
+            });
+
+        extern "system" fn shim<F>(
+            _: WebViewID,
+            context: *mut c_void,
+            frame_id: miniblink_sys::mbWebFrameHandle,
+            url: *const c_char,
+            result: miniblink_sys::mbLoadingResult,
+            failed_reason: *const c_char,
+        ) where
+            F: OnLoadingFinish,
+        {
+            let context = unsafe { CallBackContext::<F>::from_param(context) };
+            let Some(inner) = context.webview.upgrade() else {
+                return;
+            };
+
+            let webview = WebView { inner };
+            let url = unsafe { CStr::from_ptr(url).to_string_lossy().to_string() };
+            let failed_reason = match failed_reason.is_null() {
+                true => String::new(),
+                false => unsafe { CStr::from_ptr(failed_reason).to_string_lossy().to_string() },
+            };
+            let params = LoadingFinishParameters {
+                frame_id: WebFrameHandle { inner: frame_id },
+                url,
+                result: LoadingResult::from_mb(result),
+                failed_reason,
+            };
+
+            let _ = catch_unwind(AssertUnwindSafe(|| (context.content)(&webview, &params)));
+        }
+
+        unsafe { api.mbOnLoadingFinish(id, Some(shim::<F>), context as _) };
+        Ok(pending.registered())
+    }
+
+    /// Get the load state of the main frame.
+    ///
+    /// # Remarks
+    /// The state is tracked through the event bus from the creation of the webview. Registering
+    /// `on_navigation`, `on_document_ready` or `on_loading_finish` directly stops the tracking of
+    /// that event, see `events::Event`.
+    ///
+    /// ```rust,no_run
+    /// use miniblink::{app, webview::WebView};
+    ///
+    /// app::init("./mb.dll").unwrap();
+    /// let view = WebView::default();
+    /// if view.load_state().is_loading() {
+    ///     view.stop_loading();
+    /// }
+    /// ```
+    pub fn load_state(&self) -> LoadState {
+        self.inner.load.lock().unwrap().state.clone()
+    }
+
+    /// Load the url of the last failed or canceled load again. Does nothing otherwise.
+    pub fn retry_load(&self) {
+        self.try_retry_load().unwrap_or_gone()
+    }
+
+    /// Fallible version of `retry_load`.
+    pub fn try_retry_load(&self) -> MBResult<()> {
+        match self.load_state() {
+            LoadState::Failed { url, .. } | LoadState::Canceled { url } => self.try_load_url(&url),
+            _ => Ok(()),
+        }
+    }
+
+    /// Show the html returned by `handler` when the main frame fails to load.
+    ///
+    /// The handler is called with the failure, and returns `None` to keep the default blank page.
+    /// The page is loaded with the failed url as its base, so `go_back` returns to the page before
+    /// the failure, and `load_state` and `retry_load` keep referring to the failed url.
+    ///
+    /// # Remarks
+    /// miniblink loads pages with HTTP error codes successfully. A main frame answered with a
+    /// status of 400 or more is reported as `LoadFailureKind::Http`, with the status line of the
+    /// response as the reason, and `load_state` is `Failed` as well.
+    ///
+    /// ```rust,no_run
+    /// use miniblink::{app, types::LoadFailureKind, webview::WebView};
+    ///
+    /// app::init("./mb.dll").unwrap();
+    /// let view = WebView::default();
+    /// view.set_error_page_handler(|_, failure| match (failure.kind, failure.status) {
+    ///     (LoadFailureKind::Http, Some(404)) => Some("<h1>Not found</h1>".into()),
+    ///     _ => None,
+    /// });
+    /// ```
+    pub fn set_error_page_handler<F>(&self, handler: F)
+    where
+        F: Fn(&WebView, &LoadFailure) -> Option<String> + Send + 'static,
+    {
+        self.inner.load.lock().unwrap().error_page = Some(Arc::new(handler));
+    }
+
+    /// Remove the handler set by `set_error_page_handler`.
+    pub fn remove_error_page_handler(&self) {
+        self.inner.load.lock().unwrap().error_page = None;
+    }
+
+    /// Set a callback when the page writes to the console, including uncaught exceptions.
+    ///
+    /// ```rust,no_run
+    /// use miniblink::{app, webview::WebView};
+    ///
+    /// app::init("./mb.dll").unwrap();
+    /// let view = WebView::default();
+    /// view.on_console(|_, msg| println!("{}:{} {}", msg.source, msg.line, msg.message));
+    /// ```
+    pub fn on_console<F>(&self, callback: F) -> CallbackHandle
+    where
+        F: OnConsole,
+    {
+        self.try_on_console(callback).unwrap_or_gone()
+    }
+
+    /// Fallible version of `on_console`.
+    pub fn try_on_console<F>(&self, callback: F) -> MBResult<CallbackHandle>
+    where
+        F: OnConsole,
+    {
+        events::set_direct::<events::Console, _>(self, callback)
+    }
+
+    /// Install the native callback of `events::Console`.
+    pub(crate) fn install_on_console<F>(&self, callback: F) -> MBResult<CallbackHandle>
+    where
+        F: OnConsole,
+    {
+        let api = crate::call_symbol("mbOnConsole")?;
+        let id = self.try_id()?;
+        let (context, pending) =
+            self.store_callback_context("mbOnConsole", callback, |id| unsafe {
+                let _ = mb_call!(mbOnConsole(id, None, std::ptr::null_mut()));
+            });
+
+        extern "system" fn shim<F>(
+            _: WebViewID,
+            context: *mut c_void,
+            level: miniblink_sys::mbConsoleLevel,
+            message: *const c_char,
+            source_name: *const c_char,
+            source_line: c_uint,
+            stack_trace: *const c_char,
+        ) where
+            F: OnConsole,
+        {
+            let context = unsafe { CallBackContext::<F>::from_param(context) };
+            let Some(inner) = context.webview.upgrade() else {
+                return;
+            };
+
+            let webview = WebView { inner };
+            let to_string = |ptr: *const c_char| match ptr.is_null() {
+                true => String::new(),
+                false => unsafe { CStr::from_ptr(ptr).to_string_lossy().to_string() },
+            };
+            let params = ConsoleMessage {
+                level: ConsoleLevel::from_mb(level),
+                message: to_string(message),
+                source: to_string(source_name),
+                line: source_line,
+                stack_trace: to_string(stack_trace),
+            };
+
+            let _ = catch_unwind(AssertUnwindSafe(|| (context.content)(&webview, &params)));
+        }
+
+        unsafe { api.mbOnConsole(id, Some(shim::<F>), context as _) };
+        Ok(pending.registered())
+    }
+
+    /// Forward the console messages to the `log` crate, with the page URL as the target and the
+    /// script location after the message.
+    ///
+    /// Errors are logged as `Error`, warnings as `Warn`, debug messages as `Debug` and others as
+    /// `Info`. Listens through `events::Console`, so `on_console` listeners added with
+    /// `subscribe` keep working.
+    #[cfg(feature = "log")]
+    pub fn forward_console_to_log(&self) -> Subscription {
+        self.try_forward_console_to_log().unwrap_or_gone()
+    }
+
+    /// Fallible version of `forward_console_to_log`.
+    #[cfg(feature = "log")]
+    pub fn try_forward_console_to_log(&self) -> MBResult<Subscription> {
+        self.try_subscribe::<events::Console, _>(0, |webview, msg| {
+            let level = match msg.level {
+                ConsoleLevel::Error | ConsoleLevel::RevokedError => log::Level::Error,
+                ConsoleLevel::Warning => log::Level::Warn,
+                ConsoleLevel::Debug => log::Level::Debug,
+                ConsoleLevel::Log | ConsoleLevel::Info => log::Level::Info,
+            };
+            let url = unsafe { mb_call!(mbGetUrl(webview.as_id())) }
+                .ok()
+                .filter(|url| !url.is_null())
+                .map(|url| unsafe { CStr::from_ptr(url).to_string_lossy().to_string() })
+                .unwrap_or_default();
+            log::log!(target: &url, level, "{} ({}:{})", msg.message, msg.source, msg.line);
+        })
+    }
+
+    /// Wait until the main frame finished loading, failed or is canceled.
+    ///
+    /// # Remarks
+    /// Listens through `events::LoadingFinish`, so it does not replace `on_loading_finish`
+    /// listeners added with `subscribe`.
+    ///
+    /// ```rust,no_run
+    /// use miniblink::{error::MBResult, types::LoadingResult, webview::WebView};
+    ///
+    /// // Awaited from a task running off the UI thread.
+    /// async fn open(view: &WebView) -> MBResult<LoadingResult> {
+    ///     view.load_url("https://miniblink.net/");
+    ///     view.wait_for_load().await
+    /// }
+    /// ```
+    pub fn wait_for_load(&self) -> MBFuture<LoadingResult> {
+        let (future, completer) = MBFuture::pending();
+        let subscription: Arc<Mutex<Option<Subscription>>> = Arc::default();
+        let slot = subscription.clone();
+        let unsubscribe = move |slot: &Mutex<Option<Subscription>>| {
+            if let Some(subscription) = slot.lock().unwrap().take() {
+                subscription.unsubscribe();
+            }
+        };
+
+        // Off the UI thread the listener unsubscribes itself on the next event instead.
+        let abandoned = subscription.clone();
+        completer.on_abandon(move || {
+            if crate::app::check_ui_thread().is_ok() {
+                unsubscribe(&abandoned);
+            }
+        });
+        let completer = Mutex::new(Some(completer));
+
+        let listener = move |webview: &WebView, params: &LoadingFinishParameters| {
+            let mut completer = completer.lock().unwrap();
+            if completer.as_ref().is_none_or(Completer::is_abandoned) {
+                drop(completer.take());
+                return unsubscribe(&slot);
+            }
+            let is_mainframe =
+                unsafe { mb_call!(mbIsMainFrame(webview.as_id(), params.frame_id.as_ptr())) };
+            if !matches!(is_mainframe, Ok(x) if x != 0) {
+                return;
+            }
+            if let Some(completer) = completer.take() {
+                completer.complete(Ok(params.result));
+            }
+            unsubscribe(&slot);
+        };
+        match self.try_subscribe::<events::LoadingFinish, _>(0, listener) {
+            Ok(x) => *subscription.lock().unwrap() = Some(x),
+            Err(error) => return MBFuture::ready(Err(error)),
+        }
+        future
+    }
+
+    /// Set a callback when the page emits download event. Some links are called when they trigger a download.
+    pub fn on_download<F>(&self, callback: F) -> CallbackHandle
+    where
+        F: OnDownload,
+    {
+        self.try_on_download(callback).unwrap_or_gone()
+    }
+
+    /// Fallible version of `on_download`.
+    pub fn try_on_download<F>(&self, callback: F) -> MBResult<CallbackHandle>
+    where
+        F: OnDownload,
+    {
+        let api = crate::call_symbol("mbOnDownload")?;
+        let id = self.try_id()?;
+        let (context, pending) =
+            self.store_callback_context("mbOnDownload", callback, |id| unsafe {
+                let _ = mb_call!(mbOnDownload(id, None, std::ptr::null_mut()));
+            });
+
+        extern "system" fn shim<F>(
+            _: WebViewID,
+            context: *mut c_void,
+            frame_id: miniblink_sys::mbWebFrameHandle,
+            url: *const c_char,
+            download_job: *mut c_void,
+        ) -> c_int
+        where
+            F: OnDownload,
+        {
+            let context = unsafe { CallBackContext::<F>::from_param(context) };
+            let Some(inner) = context.webview.upgrade() else {
+                return 1;
+            };
+
             let webview = WebView { inner };
             let frame_id = WebFrameHandle { inner: frame_id };
+            let url = unsafe { CStr::from_ptr(url).to_string_lossy().to_string() };
+            let download_job = DownloadJob {
+                inner: download_job,
+            };
+            let params = DownloadParameters {
+                frame_id,
+                url,
+                download_job,
+            };
+
+            match catch_unwind(AssertUnwindSafe(|| (context.content)(&webview, &params))) {
+                Ok(true) => 1,
+                _ => 0,
+            }
+        }
+
+        unsafe { api.mbOnDownload(id, Some(shim::<F>), context as _) };
+        Ok(pending.registered())
+    }
+
+    /// Set load URL begin callback.
+    ///
+    /// # Returns
+    /// Returns true to cancel loading, false to continue loading.
+    pub fn on_load_url_begin<F>(&self, callback: F) -> CallbackHandle
+    where
+        F: OnLoadUrlBegin,
+    {
+        self.try_on_load_url_begin(callback).unwrap_or_gone()
+    }
+
+    /// Fallible version of `on_load_url_begin`.
+    pub fn try_on_load_url_begin<F>(&self, callback: F) -> MBResult<CallbackHandle>
+    where
+        F: OnLoadUrlBegin,
+    {
+        let api = crate::call_symbol("mbOnLoadUrlBegin")?;
+        let id = self.try_id()?;
+        let (context, pending) =
+            self.store_callback_context("mbOnLoadUrlBegin", callback, |id| unsafe {
+                let _ = mb_call!(mbOnLoadUrlBegin(id, None, std::ptr::null_mut()));
+            });
+
+        extern "system" fn shim<F>(
+            _: WebViewID,
+            context: *mut c_void,
+            url: *const c_char,
+            job: *mut c_void,
+        ) -> c_int
+        where
+            F: OnLoadUrlBegin,
+        {
+            let context = unsafe { CallBackContext::<F>::from_param(context) };
+            let Some(inner) = context.webview.upgrade() else {
+                return 0;
+            };
+
+            let webview = WebView { inner };
+            let url = unsafe { CStr::from_ptr(url).to_string_lossy().to_string() };
+            let job: NetJob = NetJob { inner: job };
+
+            match catch_unwind(AssertUnwindSafe(|| (context.content)(&webview, &url, &job))) {
+                Ok(false) => 0,
+                _ => 1,
+            }
+        }
+
+        unsafe { api.mbOnLoadUrlBegin(id, Some(shim::<F>), context as _) };
+        Ok(pending.registered())
+    }
+
+    /// Set load URL end callback.
+    pub fn on_load_url_end<F>(&self, callback: F) -> CallbackHandle
+    where
+        F: OnLoadUrlEnd,
+    {
+        self.try_on_load_url_end(callback).unwrap_or_gone()
+    }
+
+    /// Fallible version of `on_load_url_end`.
+    pub fn try_on_load_url_end<F>(&self, callback: F) -> MBResult<CallbackHandle>
+    where
+        F: OnLoadUrlEnd,
+    {
+        let api = crate::call_symbol("mbOnLoadUrlEnd")?;
+        let id = self.try_id()?;
+        let (context, pending) =
+            self.store_callback_context("mbOnLoadUrlEnd", callback, |id| unsafe {
+                let _ = mb_call!(mbOnLoadUrlEnd(id, None, std::ptr::null_mut()));
+            });
+
+        extern "system" fn shim<F>(
+            _: WebViewID,
+            context: *mut c_void,
+            url: *const c_char,
+            job: *mut c_void,
+            buf: *mut c_void,
+            len: c_int,
+        ) where
+            F: OnLoadUrlEnd,
+        {
+            let context = unsafe { CallBackContext::<F>::from_param(context) };
+            let Some(inner) = context.webview.upgrade() else {
+                return;
+            };
+
+            let webview = WebView { inner };
+            let url = unsafe { CStr::from_ptr(url).to_string_lossy().to_string() };
+            let job: NetJob = NetJob { inner: job };
+            let buf = unsafe { std::slice::from_raw_parts_mut(buf as *mut u8, len as usize) };
 
-            let _ = catch_unwind(AssertUnwindSafe(|| (context.content)(&webview, &frame_id)));
-        }
+            let _ = catch_unwind(AssertUnwindSafe(|| {
+                (context.content)(&webview, &url, &job, buf)
+            }));
 
-        unsafe {
-            call_api_or_panic().mbOnDocumentReady(self.as_id(), Some(shim::<F>), context as _);
+            unsafe {
+                std::ptr::drop_in_place(buf);
+            }
         }
+
+        unsafe { api.mbOnLoadUrlEnd(id, Some(shim::<F>), context as _) };
+        Ok(pending.registered())
     }
 
-    /// Set a callback when the page emits download event. Some links are called when they trigger a download.
-    pub fn on_download<F>(&self, callback: F)
+    /// Set a callback when a network request fails.
+    pub fn on_load_url_fail<F>(&self, callback: F) -> CallbackHandle
     where
-        F: OnDownload,
+        F: OnLoadUrlFail,
+    {
+        self.try_on_load_url_fail(callback).unwrap_or_gone()
+    }
+
+    /// Fallible version of `on_load_url_fail`.
+    pub fn try_on_load_url_fail<F>(&self, callback: F) -> MBResult<CallbackHandle>
+    where
+        F: OnLoadUrlFail,
     {
-        let context = self.store_callback_context(callback);
+        let api = crate::call_symbol("mbOnLoadUrlFail")?;
+        let id = self.try_id()?;
+        let (context, pending) =
+            self.store_callback_context("mbOnLoadUrlFail", callback, |id| unsafe {
+                let _ = mb_call!(mbOnLoadUrlFail(id, None, std::ptr::null_mut()));
+            });
 
         extern "system" fn shim<F>(
             _: WebViewID,
             context: *mut c_void,
-            frame_id: miniblink_sys::mbWebFrameHandle,
             url: *const c_char,
-            download_job: *mut c_void,
-        ) -> c_int
-        where
-            F: OnDownload,
+            job: *mut c_void,
+        ) where
+            F: OnLoadUrlFail,
         {
-            let context = unsafe { &*(context as *const CallBackContext<F>) };
+            let context = unsafe { CallBackContext::<F>::from_param(context) };
             let Some(inner) = context.webview.upgrade() else {
-                return 1;
+                return;
             };
 
             let webview = WebView { inner };
-            let frame_id = WebFrameHandle { inner: frame_id };
             let url = unsafe { CStr::from_ptr(url).to_string_lossy().to_string() };
-            let download_job = DownloadJob {
-                inner: download_job,
-            };
-            let params = DownloadParameters {
-                frame_id,
-                url,
-                download_job,
-            };
+            let job: NetJob = NetJob { inner: job };
 
-            match catch_unwind(AssertUnwindSafe(|| (context.content)(&webview, &params))) {
-                Ok(true) => 1,
-                _ => 0,
-            }
+            let _ = catch_unwind(AssertUnwindSafe(|| (context.content)(&webview, &url, &job)));
         }
 
-        unsafe {
-            call_api_or_panic().mbOnDownload(self.as_id(), Some(shim::<F>), context as _);
-        }
+        unsafe { api.mbOnLoadUrlFail(id, Some(shim::<F>), context as _) };
+        Ok(pending.registered())
     }
 
-    /// Set load URL begin callback.
-    ///
-    /// # Returns
-    /// Returns true to cancel loading, false to continue loading.
-    pub fn on_load_url_begin<F>(&self, callback: F)
+    /// Set a callback when a network request finishes, with the length of the received data.
+    pub fn on_load_url_finish<F>(&self, callback: F) -> CallbackHandle
     where
-        F: OnLoadUrlBegin,
+        F: OnLoadUrlFinish,
+    {
+        self.try_on_load_url_finish(callback).unwrap_or_gone()
+    }
+
+    /// Fallible version of `on_load_url_finish`.
+    pub fn try_on_load_url_finish<F>(&self, callback: F) -> MBResult<CallbackHandle>
+    where
+        F: OnLoadUrlFinish,
     {
-        let context = self.store_callback_context(callback);
+        let api = crate::call_symbol("mbOnLoadUrlFinish")?;
+        let id = self.try_id()?;
+        let (context, pending) =
+            self.store_callback_context("mbOnLoadUrlFinish", callback, |id| unsafe {
+                let _ = mb_call!(mbOnLoadUrlFinish(id, None, std::ptr::null_mut()));
+            });
 
         extern "system" fn shim<F>(
             _: WebViewID,
             context: *mut c_void,
             url: *const c_char,
             job: *mut c_void,
-        ) -> c_int
-        where
-            F: OnLoadUrlBegin,
+            len: c_int,
+        ) where
+            F: OnLoadUrlFinish,
         {
-            let context = unsafe { &*(context as *const CallBackContext<F>) };
+            let context = unsafe { CallBackContext::<F>::from_param(context) };
             let Some(inner) = context.webview.upgrade() else {
-                return 0;
+                return;
             };
 
             let webview = WebView { inner };
             let url = unsafe { CStr::from_ptr(url).to_string_lossy().to_string() };
             let job: NetJob = NetJob { inner: job };
+            let len = len.max(0) as usize;
 
-            match catch_unwind(AssertUnwindSafe(|| (context.content)(&webview, &url, &job))) {
-                Ok(false) => 0,
-                _ => 1,
-            }
+            let _ = catch_unwind(AssertUnwindSafe(|| {
+                (context.content)(&webview, &url, &job, len)
+            }));
         }
 
-        unsafe {
-            call_api_or_panic().mbOnLoadUrlBegin(self.as_id(), Some(shim::<F>), context as _);
-        }
+        unsafe { api.mbOnLoadUrlFinish(id, Some(shim::<F>), context as _) };
+        Ok(pending.registered())
     }
 
-    /// Set load URL end callback.
-    pub fn on_load_url_end<F>(&self, callback: F)
+    /// Set a callback when the response headers of a network request are received.
+    pub fn on_load_url_headers_received<F>(&self, callback: F) -> CallbackHandle
     where
-        F: OnLoadUrlEnd,
+        F: OnLoadUrlHeadersReceived,
+    {
+        self.try_on_load_url_headers_received(callback)
+            .unwrap_or_gone()
+    }
+
+    /// Fallible version of `on_load_url_headers_received`.
+    pub fn try_on_load_url_headers_received<F>(&self, callback: F) -> MBResult<CallbackHandle>
+    where
+        F: OnLoadUrlHeadersReceived,
+    {
+        events::set_direct::<events::LoadUrlHeadersReceived, _>(self, move |webview, args| {
+            callback(webview, &args.url, &args.job)
+        })
+    }
+
+    /// Install the native callback of `events::LoadUrlHeadersReceived`.
+    pub(crate) fn install_on_load_url_headers_received<F>(
+        &self,
+        callback: F,
+    ) -> MBResult<CallbackHandle>
+    where
+        F: OnLoadUrlHeadersReceived,
     {
-        let context = self.store_callback_context(callback);
+        let api = crate::call_symbol("mbOnLoadUrlHeadersReceived")?;
+        let id = self.try_id()?;
+        let (context, pending) =
+            self.store_callback_context("mbOnLoadUrlHeadersReceived", callback, |id| unsafe {
+                let _ = mb_call!(mbOnLoadUrlHeadersReceived(id, None, std::ptr::null_mut()));
+            });
 
         extern "system" fn shim<F>(
             _: WebViewID,
             context: *mut c_void,
             url: *const c_char,
             job: *mut c_void,
-            buf: *mut c_void,
-            len: c_int,
         ) where
-            F: OnLoadUrlEnd,
+            F: OnLoadUrlHeadersReceived,
         {
-            let context = unsafe { &*(context as *const CallBackContext<F>) };
+            let context = unsafe { CallBackContext::<F>::from_param(context) };
             let Some(inner) = context.webview.upgrade() else {
                 return;
             };
@@ -827,20 +2624,12 @@ impl WebView {
             let webview = WebView { inner };
             let url = unsafe { CStr::from_ptr(url).to_string_lossy().to_string() };
             let job: NetJob = NetJob { inner: job };
-            let buf = unsafe { std::slice::from_raw_parts_mut(buf as *mut u8, len as usize) };
-
-            let _ = catch_unwind(AssertUnwindSafe(|| {
-                (context.content)(&webview, &url, &job, buf)
-            }));
 
-            unsafe {
-                std::ptr::drop_in_place(buf);
-            }
+            let _ = catch_unwind(AssertUnwindSafe(|| (context.content)(&webview, &url, &job)));
         }
 
-        unsafe {
-            call_api_or_panic().mbOnLoadUrlEnd(self.as_id(), Some(shim::<F>), context as _);
-        }
+        unsafe { api.mbOnLoadUrlHeadersReceived(id, Some(shim::<F>), context as _) };
+        Ok(pending.registered())
     }
 
     /// Set debug config
@@ -848,10 +2637,22 @@ impl WebView {
     where
         T: Into<String>,
     {
-        let key = CString::new(key).unwrap();
-        let value = CString::new(value.into()).unwrap();
+        self.try_set_debug_config(key, value).unwrap_or_gone()
+    }
+
+    /// Fallible version of `set_debug_config`.
+    pub fn try_set_debug_config<T>(&self, key: &str, value: T) -> MBResult<()>
+    where
+        T: Into<String>,
+    {
+        let key = to_cstring(key)?;
+        let value = to_cstring(value.into())?;
         unsafe {
-            call_api_or_panic().mbSetDebugConfig(self.as_id(), key.as_ptr(), value.as_ptr());
+            mb_call!(mbSetDebugConfig(
+                self.try_id()?,
+                key.as_ptr(),
+                value.as_ptr()
+            ))
         }
     }
 
@@ -861,7 +2662,12 @@ impl WebView {
     ///
     /// This function should only used in off screen render mode.
     pub fn set_handle(&self, handle: WindowHandle) {
-        unsafe { call_api_or_panic().mbSetHandle(self.as_id(), handle.inner as _) };
+        self.try_set_handle(handle).unwrap_or_gone()
+    }
+
+    /// Fallible version of `set_handle`.
+    pub fn try_set_handle(&self, handle: WindowHandle) -> MBResult<()> {
+        unsafe { mb_call!(mbSetHandle(self.try_id()?, handle.inner as _)) }
     }
 
     /// Set handle offset.
@@ -870,131 +2676,291 @@ impl WebView {
     ///
     /// This function should only used in off screen render mode.
     pub fn set_handle_offset(&self, x: i32, y: i32) {
-        unsafe { call_api_or_panic().mbSetHandleOffset(self.as_id(), x, y) };
+        self.try_set_handle_offset(x, y).unwrap_or_gone()
+    }
+
+    /// Fallible version of `set_handle_offset`.
+    pub fn try_set_handle_offset(&self, x: i32, y: i32) -> MBResult<()> {
+        unsafe { mb_call!(mbSetHandleOffset(self.try_id()?, x, y)) }
     }
 
     /// Set user agent.
     pub fn set_user_agent(&self, user_agent: &str) {
-        let user_agent = CString::new(user_agent).unwrap();
-        unsafe {
-            call_api_or_panic().mbSetUserAgent(self.as_id(), user_agent.as_ptr());
-        }
+        self.try_set_user_agent(user_agent).unwrap_or_gone()
+    }
+
+    /// Fallible version of `set_user_agent`.
+    pub fn try_set_user_agent(&self, user_agent: &str) -> MBResult<()> {
+        let user_agent = to_cstring(user_agent)?;
+        unsafe { mb_call!(mbSetUserAgent(self.try_id()?, user_agent.as_ptr())) }
     }
 
     /// Load URL.
     pub fn load_url(&self, url: &str) {
-        let url = CString::new(url).unwrap();
-        unsafe {
-            call_api_or_panic().mbLoadURL(self.as_id(), url.as_ptr());
-        }
+        self.try_load_url(url).unwrap_or_gone()
+    }
+
+    /// Fallible version of `load_url`.
+    pub fn try_load_url(&self, url: &str) -> MBResult<()> {
+        let url = to_cstring(url)?;
+        unsafe { mb_call!(mbLoadURL(self.try_id()?, url.as_ptr())) }
     }
 
     /// Load HTML with base URL.
     pub fn load_html_with_base_url(&self, html: &str, base_url: &str) {
-        let html = CString::new(html).unwrap();
-        let base_url = CString::new(base_url).unwrap();
+        self.try_load_html_with_base_url(html, base_url)
+            .unwrap_or_gone()
+    }
+
+    /// Fallible version of `load_html_with_base_url`.
+    pub fn try_load_html_with_base_url(&self, html: &str, base_url: &str) -> MBResult<()> {
+        let html = to_cstring(html)?;
+        let base_url = to_cstring(base_url)?;
         unsafe {
-            call_api_or_panic().mbLoadHtmlWithBaseUrl(
-                self.as_id(),
+            mb_call!(mbLoadHtmlWithBaseUrl(
+                self.try_id()?,
                 html.as_ptr(),
                 base_url.as_ptr(),
-            )
+            ))
         }
     }
 
     /// Enable context menu.
     pub fn enable_context_menu(&self, enabled: bool) {
-        unsafe { call_api_or_panic().mbSetContextMenuEnabled(self.as_id(), enabled as _) }
+        self.try_enable_context_menu(enabled).unwrap_or_gone()
+    }
+
+    /// Fallible version of `enable_context_menu`.
+    pub fn try_enable_context_menu(&self, enabled: bool) -> MBResult<()> {
+        unsafe { mb_call!(mbSetContextMenuEnabled(self.try_id()?, enabled as _)) }
     }
 
     /// Enable cookie.
     pub fn enable_cookie(&self, enabled: bool) {
-        unsafe { call_api_or_panic().mbSetCookieEnabled(self.as_id(), enabled as _) }
+        self.try_enable_cookie(enabled).unwrap_or_gone()
+    }
+
+    /// Fallible version of `enable_cookie`.
+    pub fn try_enable_cookie(&self, enabled: bool) -> MBResult<()> {
+        unsafe { mb_call!(mbSetCookieEnabled(self.try_id()?, enabled as _)) }
     }
 
     /// Enable CSP check.
     pub fn enable_csp_check(&self, enabled: bool) {
-        unsafe { call_api_or_panic().mbSetCspCheckEnable(self.as_id(), enabled as _) }
+        self.try_enable_csp_check(enabled).unwrap_or_gone()
+    }
+
+    /// Fallible version of `enable_csp_check`.
+    pub fn try_enable_csp_check(&self, enabled: bool) -> MBResult<()> {
+        unsafe { mb_call!(mbSetCspCheckEnable(self.try_id()?, enabled as _)) }
     }
 
     /// Enable disk cache.
     pub fn enable_disk_cache(&self, enabled: bool) {
-        unsafe { call_api_or_panic().mbSetDiskCacheEnabled(self.as_id(), enabled as _) }
+        self.try_enable_disk_cache(enabled).unwrap_or_gone()
+    }
+
+    /// Fallible version of `enable_disk_cache`.
+    pub fn try_enable_disk_cache(&self, enabled: bool) -> MBResult<()> {
+        unsafe { mb_call!(mbSetDiskCacheEnabled(self.try_id()?, enabled as _)) }
+    }
+
+    /// Apply the cache settings, see `types::CacheSettings`.
+    pub fn configure_cache(&self, settings: &CacheSettings) {
+        self.try_configure_cache(settings).unwrap_or_gone()
+    }
+
+    /// Fallible version of `configure_cache`.
+    pub fn try_configure_cache(&self, settings: &CacheSettings) -> MBResult<()> {
+        let id = self.try_id()?;
+        if let Some(path) = &settings.path {
+            std::fs::create_dir_all(path).map_err(MBError::Io)?;
+            let wide = to_wide_cstring(path.to_str().ok_or(MBError::InvalidString)?)?;
+            unsafe { mb_call!(mbSetDiskCachePath(id, wide.as_ptr())) }?;
+            *self.inner.disk_cache.lock().unwrap() = Some(path.clone());
+        }
+        if let Some(limit) = settings.memory_limit {
+            unsafe { mb_call!(mbSetDiskCacheLimit(id, limit)) }?;
+        }
+        if let Some(limit) = settings.disk_limit {
+            unsafe { mb_call!(mbSetDiskCacheLimitDisk(id, limit)) }?;
+        }
+        if let Some(level) = settings.level {
+            unsafe { mb_call!(mbSetDiskCacheLevel(id, level)) }?;
+        }
+        if let Some(enabled) = settings.memory_cache {
+            unsafe { mb_call!(mbSetMemoryCacheEnable(id, enabled as _)) }?;
+        }
+        if let Some(interval) = settings.resource_gc_interval {
+            let secs = interval.as_secs().min(i32::MAX as u64) as i32;
+            unsafe { mb_call!(mbSetResourceGc(id, secs)) }?;
+        }
+        Ok(())
     }
 
     /// Enable drag and drop.
     pub fn enable_drag_drop(&self, enabled: bool) {
-        unsafe { call_api_or_panic().mbSetDragDropEnable(self.as_id(), enabled as _) }
+        self.try_enable_drag_drop(enabled).unwrap_or_gone()
+    }
+
+    /// Fallible version of `enable_drag_drop`.
+    pub fn try_enable_drag_drop(&self, enabled: bool) -> MBResult<()> {
+        unsafe { mb_call!(mbSetDragDropEnable(self.try_id()?, enabled as _)) }
     }
 
     /// Enable drag.
     pub fn enable_drag(&self, enabled: bool) {
-        unsafe { call_api_or_panic().mbSetDragEnable(self.as_id(), enabled as _) }
+        self.try_enable_drag(enabled).unwrap_or_gone()
+    }
+
+    /// Fallible version of `enable_drag`.
+    pub fn try_enable_drag(&self, enabled: bool) -> MBResult<()> {
+        unsafe { mb_call!(mbSetDragEnable(self.try_id()?, enabled as _)) }
     }
 
     /// Enable headless mode.
     pub fn enable_headless_mode(&self, enabled: bool) {
-        unsafe { call_api_or_panic().mbSetHeadlessEnabled(self.as_id(), enabled as _) }
+        self.try_enable_headless_mode(enabled).unwrap_or_gone()
+    }
+
+    /// Fallible version of `enable_headless_mode`.
+    pub fn try_enable_headless_mode(&self, enabled: bool) -> MBResult<()> {
+        unsafe { mb_call!(mbSetHeadlessEnabled(self.try_id()?, enabled as _)) }
     }
 
     /// Enable memory cache.
     pub fn enable_memory_cache(&self, enabled: bool) {
-        unsafe { call_api_or_panic().mbSetMemoryCacheEnable(self.as_id(), enabled as _) }
+        self.try_enable_memory_cache(enabled).unwrap_or_gone()
+    }
+
+    /// Fallible version of `enable_memory_cache`.
+    pub fn try_enable_memory_cache(&self, enabled: bool) -> MBResult<()> {
+        unsafe { mb_call!(mbSetMemoryCacheEnable(self.try_id()?, enabled as _)) }
     }
 
     /// Enable mouse.
     pub fn enable_mouse(&self, enabled: bool) {
-        unsafe { call_api_or_panic().mbSetMouseEnabled(self.as_id(), enabled as _) }
+        self.try_enable_mouse(enabled).unwrap_or_gone()
+    }
+
+    /// Fallible version of `enable_mouse`.
+    pub fn try_enable_mouse(&self, enabled: bool) -> MBResult<()> {
+        unsafe { mb_call!(mbSetMouseEnabled(self.try_id()?, enabled as _)) }
     }
 
     /// Enable navigation to new window.
     pub fn enable_navigation_to_new_window(&self, enabled: bool) {
-        unsafe { call_api_or_panic().mbSetNavigationToNewWindowEnable(self.as_id(), enabled as _) }
+        self.try_enable_navigation_to_new_window(enabled)
+            .unwrap_or_gone()
+    }
+
+    /// Fallible version of `enable_navigation_to_new_window`.
+    pub fn try_enable_navigation_to_new_window(&self, enabled: bool) -> MBResult<()> {
+        unsafe {
+            mb_call!(mbSetNavigationToNewWindowEnable(
+                self.try_id()?,
+                enabled as _
+            ))
+        }
     }
 
     /// Enable nodejs.
     pub fn enable_nodejs(&self, enabled: bool) {
-        unsafe { call_api_or_panic().mbSetNodeJsEnable(self.as_id(), enabled as _) }
+        self.try_enable_nodejs(enabled).unwrap_or_gone()
+    }
+
+    /// Fallible version of `enable_nodejs`.
+    pub fn try_enable_nodejs(&self, enabled: bool) -> MBResult<()> {
+        unsafe { mb_call!(mbSetNodeJsEnable(self.try_id()?, enabled as _)) }
     }
 
     /// Enable npapi plugins.
     pub fn enable_npapi_plugins(&self, enabled: bool) {
-        unsafe { call_api_or_panic().mbSetNpapiPluginsEnabled(self.as_id(), enabled as _) }
+        self.try_enable_npapi_plugins(enabled).unwrap_or_gone()
+    }
+
+    /// Fallible version of `enable_npapi_plugins`.
+    pub fn try_enable_npapi_plugins(&self, enabled: bool) -> MBResult<()> {
+        unsafe { mb_call!(mbSetNpapiPluginsEnabled(self.try_id()?, enabled as _)) }
     }
 
     /// Enable system touch.
     pub fn enable_system_touch(&self, enabled: bool) {
-        unsafe { call_api_or_panic().mbSetSystemTouchEnabled(self.as_id(), enabled as _) }
+        self.try_enable_system_touch(enabled).unwrap_or_gone()
+    }
+
+    /// Fallible version of `enable_system_touch`.
+    pub fn try_enable_system_touch(&self, enabled: bool) -> MBResult<()> {
+        unsafe { mb_call!(mbSetSystemTouchEnabled(self.try_id()?, enabled as _)) }
     }
 
     /// Enable touch.
     pub fn enable_touch(&self, enabled: bool) {
-        unsafe { call_api_or_panic().mbSetTouchEnabled(self.as_id(), enabled as _) }
+        self.try_enable_touch(enabled).unwrap_or_gone()
+    }
+
+    /// Fallible version of `enable_touch`.
+    pub fn try_enable_touch(&self, enabled: bool) -> MBResult<()> {
+        unsafe { mb_call!(mbSetTouchEnabled(self.try_id()?, enabled as _)) }
     }
 
     /// Check if is the mainframe.
     pub fn is_mainframe(&self, frame_handle: WebFrameHandle) -> bool {
-        let id = self.as_id();
+        self.try_is_mainframe(frame_handle).unwrap_or_gone()
+    }
+
+    /// Fallible version of `is_mainframe`.
+    pub fn try_is_mainframe(&self, frame_handle: WebFrameHandle) -> MBResult<bool> {
+        let id = self.try_id()?;
         invoke_command_sync(move || unsafe {
-            call_api_or_panic().mbIsMainFrame(id, frame_handle.as_ptr()) != 0
-        })
+            mb_call!(mbIsMainFrame(id, frame_handle.as_ptr())).map(|x| x != 0)
+        })?
     }
 
     /// Get the main_frame.
     pub fn get_mainframe(&self) -> WebFrameHandle {
-        let id = self.as_id();
+        self.try_get_mainframe().unwrap_or_gone()
+    }
+
+    /// Fallible version of `get_mainframe`.
+    pub fn try_get_mainframe(&self) -> MBResult<WebFrameHandle> {
+        let id = self.try_id()?;
         invoke_command_sync(move || {
-            let handle = unsafe { call_api_or_panic().mbWebFrameGetMainFrame(id) };
-            WebFrameHandle { inner: handle }
-        })
+            let handle = unsafe { mb_call!(mbWebFrameGetMainFrame(id)) }?;
+            Ok(WebFrameHandle { inner: handle })
+        })?
+    }
+
+    /// Get the tracked frames, in creation order. See `types::Frame`.
+    ///
+    /// # Remarks
+    /// Frames are tracked from the creation of the webview. Returns nothing if the library lacks
+    /// `mbOnDidCreateScriptContext`.
+    pub fn frames(&self) -> Vec<Frame> {
+        crate::frame::list(&self.inner, None)
+    }
+
+    /// Get the tracked main frame.
+    pub fn main_frame(&self) -> Option<Frame> {
+        crate::frame::list(&self.inner, Some(None))
+            .into_iter()
+            .next()
+    }
+
+    /// Get the tracked frame of the handle, for example from `on_document_ready`.
+    pub fn frame(&self, frame_handle: WebFrameHandle) -> Option<Frame> {
+        crate::frame::get(self, frame_handle)
     }
 
     /// Set global proxy.
     pub fn set_proxy(&self, proxy: &Proxy) {
+        self.try_set_proxy(proxy).unwrap_or_gone()
+    }
+
+    /// Fallible version of `set_proxy`.
+    pub fn try_set_proxy(&self, proxy: &Proxy) -> MBResult<()> {
         let proxy = proxy.to_mb_proxy();
-        unsafe {
-            call_api_or_panic().mbSetProxy(self.as_id(), &proxy);
-        }
+        unsafe { mb_call!(mbSetProxy(self.try_id()?, &proxy)) }
     }
 
     /// Create a new webview window.
@@ -1002,33 +2968,59 @@ impl WebView {
     /// # Remarks
     /// In Linux, transparent window is not supported, instead popup window is created.
     pub fn new(typ: WindowType, x: i32, y: i32, width: i32, height: i32) -> Self {
+        Self::try_new(typ, x, y, width, height).unwrap_or_gone()
+    }
+
+    /// Fallible version of `new`.
+    pub fn try_new(typ: WindowType, x: i32, y: i32, width: i32, height: i32) -> MBResult<Self> {
         let id = unsafe {
-            call_api_or_panic().mbCreateWebWindow(
+            mb_call!(mbCreateWebWindow(
                 typ as _,
                 std::ptr::null_mut(),
                 x,
                 y,
                 width,
                 height,
-            )
-        };
-        let webview = unsafe { Self::from_raw(id) };
-        // set_webwindow_handler(&webview);
-        webview
+            ))
+        }?;
+        match id {
+            0 => Err(MBError::CreateWebViewFailed),
+            id => Ok(unsafe { Self::from_raw(id) }),
+        }
     }
 
     /// Set close callback.
-    pub fn on_close<F>(&self, callback: F)
+    pub fn on_close<F>(&self, callback: F) -> CallbackHandle
+    where
+        F: OnClose,
+    {
+        self.try_on_close(callback).unwrap_or_gone()
+    }
+
+    /// Fallible version of `on_close`.
+    pub fn try_on_close<F>(&self, callback: F) -> MBResult<CallbackHandle>
+    where
+        F: OnClose,
+    {
+        events::set_direct::<events::Close, _>(self, move |webview, _| callback(webview))
+    }
+
+    /// Install the native callback of `events::Close`.
+    pub(crate) fn install_on_close<F>(&self, callback: F) -> MBResult<CallbackHandle>
     where
         F: OnClose,
     {
-        let context = self.store_callback_context(callback);
+        let api = crate::call_symbol("mbOnClose")?;
+        let id = self.try_id()?;
+        let (context, pending) = self.store_callback_context("mbOnClose", callback, |id| unsafe {
+            let _ = mb_call!(mbOnClose(id, None, std::ptr::null_mut()));
+        });
 
         extern "system" fn shim<F>(_: WebViewID, param: *mut c_void, _: *mut c_void) -> c_int
         where
             F: OnClose,
         {
-            let context = unsafe { &*(param as *const CallBackContext<F>) };
+            let context = unsafe { CallBackContext::<F>::from_param(param) };
             let Some(inner) = context.webview.upgrade() else {
                 return 1;
             };
@@ -1041,23 +3033,43 @@ impl WebView {
             }
         }
 
-        unsafe {
-            call_api_or_panic().mbOnClose(self.as_id(), Some(shim::<F>), context as _);
-        }
+        unsafe { api.mbOnClose(id, Some(shim::<F>), context as _) };
+        Ok(pending.registered())
     }
 
     /// Set destroy callback.
-    pub fn on_destroy<F>(&self, callback: F)
+    pub fn on_destroy<F>(&self, callback: F) -> CallbackHandle
+    where
+        F: OnDestroy,
+    {
+        self.try_on_destroy(callback).unwrap_or_gone()
+    }
+
+    /// Fallible version of `on_destroy`.
+    pub fn try_on_destroy<F>(&self, callback: F) -> MBResult<CallbackHandle>
+    where
+        F: OnDestroy,
+    {
+        events::set_direct::<events::Destroy, _>(self, move |webview, _| callback(webview))
+    }
+
+    /// Install the native callback of `events::Destroy`.
+    pub(crate) fn install_on_destroy<F>(&self, callback: F) -> MBResult<CallbackHandle>
     where
         F: OnDestroy,
     {
-        let context = self.store_callback_context(callback);
+        let api = crate::call_symbol("mbOnDestroy")?;
+        let id = self.try_id()?;
+        let (context, pending) =
+            self.store_callback_context("mbOnDestroy", callback, |id| unsafe {
+                let _ = mb_call!(mbOnDestroy(id, None, std::ptr::null_mut()));
+            });
 
         extern "system" fn shim<F>(_: WebViewID, param: *mut c_void, _: *mut c_void) -> c_int
         where
             F: OnDestroy,
         {
-            let context = unsafe { &*(param as *const CallBackContext<F>) };
+            let context = unsafe { CallBackContext::<F>::from_param(param) };
             let Some(inner) = context.webview.upgrade() else {
                 return 1;
             };
@@ -1070,23 +3082,60 @@ impl WebView {
             }
         }
 
-        unsafe {
-            call_api_or_panic().mbOnDestroy(self.as_id(), Some(shim::<F>), context as _);
-        }
+        unsafe { api.mbOnDestroy(id, Some(shim::<F>), context as _) };
+        Ok(pending.registered())
+    }
+
+    /// Add a listener of the event `E`. Any number of listeners can subscribe to the same event.
+    ///
+    /// Listeners with a higher priority are called first.
+    ///
+    /// ```rust,no_run
+    /// use miniblink::{app, events, webview::WebView};
+    ///
+    /// app::init("./mb.dll").unwrap();
+    /// let view = WebView::default();
+    /// let router = view.subscribe::<events::Navigation, _>(10, |_, params| {
+    ///     !params.url.starts_with("https://blocked.example")
+    /// });
+    /// router.unsubscribe();
+    /// ```
+    pub fn subscribe<E, F>(&self, priority: i32, listener: F) -> Subscription
+    where
+        E: Event,
+        F: Fn(&WebView, &E::Args) -> E::Output + Send + 'static,
+    {
+        self.try_subscribe::<E, F>(priority, listener)
+            .unwrap_or_gone()
+    }
+
+    /// Fallible version of `subscribe`.
+    pub fn try_subscribe<E, F>(&self, priority: i32, listener: F) -> MBResult<Subscription>
+    where
+        E: Event,
+        F: Fn(&WebView, &E::Args) -> E::Output + Send + 'static,
+    {
+        events::subscribe::<E, F>(self, priority, listener)
     }
 
     /// Show the window.
     pub fn show(&self) {
-        unsafe {
-            call_api_or_panic().mbShowWindow(self.as_id(), 1);
-        }
+        self.try_show().unwrap_or_gone()
+    }
+
+    /// Fallible version of `show`.
+    pub fn try_show(&self) -> MBResult<()> {
+        unsafe { mb_call!(mbShowWindow(self.try_id()?, 1)) }
     }
 
     /// Hide the window.
     pub fn hide(&self) {
-        unsafe {
-            call_api_or_panic().mbShowWindow(self.as_id(), 0);
-        }
+        self.try_hide().unwrap_or_gone()
+    }
+
+    /// Fallible version of `hide`.
+    pub fn try_hide(&self) -> MBResult<()> {
+        unsafe { mb_call!(mbShowWindow(self.try_id()?, 0)) }
     }
 
     // /// Resize the window.
@@ -1096,32 +3145,66 @@ impl WebView {
 
     /// Move the window.
     pub fn move_window(&self, x: i32, y: i32, width: i32, height: i32) {
-        unsafe {
-            call_api_or_panic().mbMoveWindow(self.as_id(), x, y, width, height);
-        }
+        self.try_move_window(x, y, width, height).unwrap_or_gone()
+    }
+
+    /// Fallible version of `move_window`.
+    pub fn try_move_window(&self, x: i32, y: i32, width: i32, height: i32) -> MBResult<()> {
+        unsafe { mb_call!(mbMoveWindow(self.try_id()?, x, y, width, height)) }
     }
 
     /// Move the window to center.
     pub fn move_to_center(&self) {
-        unsafe {
-            call_api_or_panic().mbMoveToCenter(self.as_id());
-        }
+        self.try_move_to_center().unwrap_or_gone()
+    }
+
+    /// Fallible version of `move_to_center`.
+    pub fn try_move_to_center(&self) -> MBResult<()> {
+        unsafe { mb_call!(mbMoveToCenter(self.try_id()?)) }
     }
 
     /// Set the window title.
     pub fn set_window_title(&self, title: &str) {
-        let title = CString::new(title).unwrap();
-        unsafe { call_api_or_panic().mbSetWindowTitle(self.as_id(), title.as_ptr()) }
+        self.try_set_window_title(title).unwrap_or_gone()
+    }
+
+    /// Fallible version of `set_window_title`.
+    pub fn try_set_window_title(&self, title: &str) -> MBResult<()> {
+        let title = to_cstring(title)?;
+        unsafe { mb_call!(mbSetWindowTitle(self.try_id()?, title.as_ptr())) }
     }
 
-    fn store_callback_context<T>(&self, callback: T) -> *const CallBackContext<T>
+    /// Store the context of the callback registered through `setter`.
+    ///
+    /// The previous context is freed by `PendingCallback::registered`, once the new shim replaced
+    /// it in miniblink. `unset` passes `None` to the setter when the returned handle is revoked.
+    fn store_callback_context<T>(
+        &self,
+        setter: &'static str,
+        callback: T,
+        unset: fn(WebViewID),
+    ) -> (*const CallBackContext<T>, PendingCallback)
     where
         T: Send + 'static,
     {
-        let context = CallBackContext::new(self, callback);
-        let param = &*context as *const _;
-        self.inner.callbacks.lock().unwrap().push(context);
-        param
+        let context = Arc::new(CallBackContext {
+            webview: Arc::downgrade(&self.inner),
+            content: callback,
+        });
+        let param = Arc::as_ptr(&context);
+        let token = NEXT_CALLBACK_TOKEN.fetch_add(1, Ordering::Relaxed);
+        let stored = StoredCallback {
+            token,
+            unset: Unset::Native(unset),
+            _context: context,
+        };
+        let previous = self.inner.callbacks.lock().unwrap().insert(setter, stored);
+        let handle = CallbackHandle {
+            webview: Arc::downgrade(&self.inner),
+            setter,
+            token,
+        };
+        (param, PendingCallback { handle, previous })
     }
 
     fn push_child(&self, child: WebView) -> WebViewID {
@@ -1165,8 +3248,83 @@ impl Default for WebView {
     }
 }
 
+impl WebViewInner {
+    /// Unregister the callback of `setter` if it is still the one registered with `token`.
+    pub(crate) fn revoke_callback(&self, setter: &'static str, token: u64) {
+        let stored = {
+            let mut callbacks = self.callbacks.lock().unwrap();
+            match callbacks.get(setter) {
+                Some(stored) if stored.token == token => callbacks.remove(setter),
+                _ => None,
+            }
+        };
+        if let Some(stored) = stored {
+            stored.unset(self);
+        }
+    }
+
+    /// Store the handle of the bus listener set by an `on_*` method, removing the previous one.
+    pub(crate) fn store_listener(
+        self: &Arc<Self>,
+        key: &'static str,
+        token: u64,
+        unset: fn(&WebViewInner, u64),
+    ) -> CallbackHandle {
+        let stored = StoredCallback {
+            token,
+            unset: Unset::Listener(unset),
+            _context: Arc::new(()),
+        };
+        let previous = self.callbacks.lock().unwrap().insert(key, stored);
+        if let Some(previous) = previous {
+            previous.unset(self);
+        }
+        CallbackHandle {
+            webview: Arc::downgrade(self),
+            setter: key,
+            token,
+        }
+    }
+
+    /// Check if the callback of `setter` is still the one registered with `token`.
+    pub(crate) fn has_callback(&self, setter: &'static str, token: u64) -> bool {
+        let callbacks = self.callbacks.lock().unwrap();
+        matches!(callbacks.get(setter), Some(stored) if stored.token == token)
+    }
+
+    fn destroy(&self) {
+        if self.destroyed.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        let parent = self.parent.lock().unwrap().take();
+        if let Some(parent) = parent.and_then(|x| x.upgrade()) {
+            parent
+                .childset
+                .lock()
+                .unwrap()
+                .retain(|x| x.inner.id != self.id);
+        }
+
+        let children: Vec<WebView> = self.childset.lock().unwrap().drain().collect();
+        for child in &children {
+            *child.inner.parent.lock().unwrap() = None;
+            child.inner.destroy();
+        }
+
+        if call_backend().is_ok() {
+            let id = self.id;
+            let _ = invoke_command_sync(move || unsafe { mb_call!(mbDestroyWebView(id)) });
+        }
+
+        if let Some(storage) = self.storage.lock().unwrap().take() {
+            storage.remove_temporary();
+        }
+    }
+}
+
 impl Drop for WebViewInner {
     fn drop(&mut self) {
-        unsafe { call_api_or_panic().mbDestroyWebView(self.id) };
+        self.destroy();
     }
 }
@@ -0,0 +1,69 @@
+mod common;
+
+use miniblink::backend::{MockValue, SYMBOLS};
+use miniblink::error::MBError;
+use miniblink::types::InitSettings;
+use miniblink::{app, webview::WebView};
+use miniblink_sys::Library;
+
+#[test]
+fn mock_records_calls_and_fires_callbacks() {
+    let mock = common::setup();
+    let view = WebView::default();
+    view.on_navigation(|_, params| !params.url.starts_with("https://blocked.example"));
+    assert_eq!(
+        mock.fire_navigation(view.as_id(), 0, "https://blocked.example/"),
+        Some(false)
+    );
+    assert_eq!(
+        mock.fire_navigation(view.as_id(), 0, "https://miniblink.net/"),
+        Some(true)
+    );
+
+    view.load_url("https://miniblink.net/");
+    let call = mock.calls_to("mbLoadURL").pop().unwrap();
+    assert_eq!(call.args[1].as_str(), Some("https://miniblink.net/"));
+}
+
+#[test]
+fn unsupported_function_is_reported() {
+    let mock = common::setup();
+    mock.unsupported("mbSetZoomFactor");
+
+    assert!(!app::capabilities().unwrap().has("mbSetZoomFactor"));
+    let view = WebView::default();
+    assert!(matches!(
+        view.try_set_zoom_factor(2.0),
+        Err(MBError::Unsupported("mbSetZoomFactor"))
+    ));
+    assert!(mock.calls_to("mbSetZoomFactor").is_empty());
+}
+
+#[test]
+fn reset_forgets_responders() {
+    let mock = common::setup();
+    mock.respond("mbGetZoomFactor", |_| Some(MockValue::Float(2.0)));
+    mock.unsupported("mbReload");
+    mock.reset();
+
+    let view = WebView::default();
+    assert_eq!(view.get_zoom_factor(), 0.0);
+    view.try_reload().unwrap();
+    assert_eq!(mock.calls_to("mbReload").len(), 1);
+}
+
+#[test]
+fn init_with_settings_after_init_is_rejected() {
+    let _mock = common::setup();
+    let result = app::init_with_settings("./mb.dll", InitSettings::new());
+    assert!(matches!(result, Err(MBError::AlreadyInitialized)));
+}
+
+#[test]
+fn every_symbol_is_known_to_the_library() {
+    let missing: Vec<_> = SYMBOLS
+        .iter()
+        .filter(|x| !Library::EXPORTS.contains(x))
+        .collect();
+    assert!(missing.is_empty(), "{missing:?}");
+}
@@ -0,0 +1,83 @@
+//! The fixture shared by the integration tests.
+#![allow(dead_code)]
+
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+
+use miniblink::{app, backend::MockBackend};
+
+/// The mock installed for the test binary, reset for the test holding it.
+pub struct Fixture {
+    mock: MockBackend,
+    _serial: MutexGuard<'static, ()>,
+}
+
+impl Deref for Fixture {
+    type Target = MockBackend;
+
+    fn deref(&self) -> &MockBackend {
+        &self.mock
+    }
+}
+
+/// Install the mock once per test binary and reset it.
+///
+/// The backend is global to the process, so the tests of a binary run one at a time.
+pub fn setup() -> Fixture {
+    static MOCK: OnceLock<MockBackend> = OnceLock::new();
+    static SERIAL: Mutex<()> = Mutex::new(());
+
+    let serial = SERIAL.lock().unwrap_or_else(|x| x.into_inner());
+    let mock = MOCK.get_or_init(|| {
+        let mock = MockBackend::new();
+        app::init_with_backend(mock.clone());
+        mock
+    });
+    mock.reset();
+    Fixture {
+        mock: mock.clone(),
+        _serial: serial,
+    }
+}
+
+/// A directory unique to the test, removed when dropped.
+pub struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    pub fn new(name: &str) -> Self {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let id = NEXT.fetch_add(1, Ordering::SeqCst);
+        let path =
+            std::env::temp_dir().join(format!("miniblink-{name}-{}-{id}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn join(&self, name: &str) -> PathBuf {
+        self.path.join(name)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
+
+/// Poll the future once with a waker which does nothing.
+pub fn poll_once<F>(future: std::pin::Pin<&mut F>) -> std::task::Poll<F::Output>
+where
+    F: std::future::Future,
+{
+    let mut cx = std::task::Context::from_waker(std::task::Waker::noop());
+    future.poll(&mut cx)
+}
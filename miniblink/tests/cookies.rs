@@ -0,0 +1,121 @@
+mod common;
+
+use std::time::{Duration, UNIX_EPOCH};
+
+use miniblink::types::{Cookie, CookieJar};
+use miniblink::webview::WebView;
+
+#[test]
+fn manager_reads_the_jar_and_sets_cookies() {
+    let mock = common::setup();
+    let dir = common::TempDir::new("cookie-manager");
+    let jar = dir.join("cookies.dat");
+    let text =
+        "# Netscape HTTP Cookie File\nnot a cookie\n.example.com\tTRUE\t/\tFALSE\t0\tsid\tabc\n";
+    std::fs::write(&jar, text).unwrap();
+    let view = WebView::default();
+    view.set_cookie_jar_full_path(jar.to_str().unwrap());
+
+    let cookies = view.cookie_manager();
+    assert_eq!(
+        cookies
+            .get("https://www.example.com/", "sid")
+            .unwrap()
+            .value,
+        "abc"
+    );
+    assert_eq!(cookies.list(|x| x.domain == ".example.com").len(), 1);
+
+    cookies.set(&Cookie::new("lang", "en", ".example.com"));
+    let call = mock.calls_to("mbSetCookie").pop().unwrap();
+    assert_eq!(call.args[1].as_str(), Some("http://example.com/"));
+    assert_eq!(
+        call.args[2].as_str(),
+        Some("lang=en; domain=.example.com; path=/")
+    );
+
+    cookies.delete("example.com", "sid");
+    let call = mock.calls_to("mbSetCookie").pop().unwrap();
+    assert!(call.args[2]
+        .as_str()
+        .unwrap()
+        .contains("expires=Thu, 01 Jan 1970 00:00:00 GMT"));
+}
+
+#[test]
+fn manager_rejects_injected_attributes() {
+    let mock = common::setup();
+    let view = WebView::default();
+
+    let cookie = Cookie::new("sid", "abc; domain=.evil.example", "example.com");
+    assert!(view.cookie_manager().try_set(&cookie).is_err());
+    assert!(mock.calls_to("mbSetCookie").is_empty());
+}
+
+#[test]
+fn import_replaces_cookies_and_reloads_the_jar() {
+    let mock = common::setup();
+    let dir = common::TempDir::new("import-cookies");
+    let (jar, seed) = (dir.join("cookies.dat"), dir.join("seed.txt"));
+    std::fs::write(&jar, ".example.com\tTRUE\t/\tFALSE\t0\tsid\told\n").unwrap();
+    std::fs::write(
+        &seed,
+        "#HttpOnly_.example.com\tTRUE\t/\tTRUE\t0\tsid\tnew\n",
+    )
+    .unwrap();
+    let view = WebView::default();
+    view.set_cookie_jar_full_path(jar.to_str().unwrap());
+
+    view.import_cookies(&seed);
+    let cookies = CookieJar::read(&jar).unwrap().cookies;
+    assert_eq!(cookies.len(), 1);
+    assert!(cookies[0].http_only && cookies[0].value == "new");
+    let reload = mock.calls_to("mbPerformCookieCommand").pop().unwrap();
+    assert_eq!(reload.args[1].as_int(), Some(3));
+}
+
+#[test]
+fn jar_line_round_trips() {
+    let line = "#HttpOnly_.example.com\tTRUE\t/\tTRUE\t0\tsid\tabc";
+    let cookie = Cookie::parse_jar_line(line).unwrap();
+    assert!(cookie.http_only && cookie.secure && cookie.expires.is_none());
+    assert!(cookie.matches("https://www.example.com/app"));
+    assert!(!cookie.matches("http://www.example.com/app"));
+    assert_eq!(cookie.to_jar_line(), line);
+}
+
+#[test]
+fn set_cookie_header_lists_the_attributes() {
+    let mut cookie = Cookie::new("sid", "abc", ".example.com");
+    cookie.expires = Some(UNIX_EPOCH + Duration::from_secs(1_700_000_000));
+    cookie.http_only = true;
+    assert_eq!(
+        cookie.to_set_cookie(),
+        "sid=abc; domain=.example.com; path=/; expires=Tue, 14 Nov 2023 22:13:20 GMT; HttpOnly"
+    );
+}
+
+#[test]
+fn fields_which_inject_attributes_are_rejected() {
+    for cookie in [
+        Cookie::new("sid", "abc; domain=.evil.example", "example.com"),
+        Cookie::new("sid=x", "abc", "example.com"),
+        Cookie::new("sid", "abc", "example.com\tTRUE"),
+        Cookie::new("sid", "a\r\nb", "example.com"),
+    ] {
+        assert!(cookie.try_to_set_cookie().is_err());
+        assert!(cookie.try_to_jar_line().is_err());
+    }
+}
+
+#[test]
+fn jar_round_trips_and_skips_invalid_cookies() {
+    let mut jar = CookieJar::default();
+    jar.cookies.push(Cookie::new("sid", "abc", ".example.com"));
+    let text = jar.to_string();
+    assert!(text.starts_with("# Netscape HTTP Cookie File\n"));
+    assert_eq!(CookieJar::parse(&text).unwrap(), jar);
+
+    jar.cookies.push(Cookie::new("bad", "a;b", ".example.com"));
+    assert_eq!(jar.to_string(), text);
+}
@@ -0,0 +1,34 @@
+mod common;
+
+use miniblink::{events, webview::WebView};
+
+#[test]
+fn higher_priority_listener_vetoes() {
+    let mock = common::setup();
+    let view = WebView::default();
+    view.subscribe::<events::Navigation, _>(0, |_, _| true);
+    let router = view.subscribe::<events::Navigation, _>(10, |_, params| {
+        !params.url.starts_with("https://blocked.example")
+    });
+    assert_eq!(
+        mock.fire_navigation(view.as_id(), 0, "https://blocked.example/"),
+        Some(false)
+    );
+
+    router.unsubscribe();
+    assert_eq!(
+        mock.fire_navigation(view.as_id(), 0, "https://blocked.example/"),
+        Some(true)
+    );
+}
+
+#[test]
+fn panicking_listener_vetoes() {
+    let mock = common::setup();
+    let view = WebView::default();
+    view.subscribe::<events::Navigation, _>(0, |_, _| panic!("guard failed"));
+    assert_eq!(
+        mock.fire_navigation(view.as_id(), 0, "https://miniblink.net/"),
+        Some(false)
+    );
+}
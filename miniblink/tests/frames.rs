@@ -0,0 +1,52 @@
+mod common;
+
+use miniblink::backend::MockValue;
+use miniblink::error::MBError;
+use miniblink::webview::WebView;
+
+/// Make the frame 2 an iframe of the main frame 1, and create both.
+fn iframe_of_main(mock: &common::Fixture, view: &WebView) {
+    mock.respond("mbGetParentWebFrameHandle", |args| match args[1].as_ptr() {
+        Some(2) => Some(MockValue::Ptr(1)),
+        _ => None,
+    });
+    mock.fire_did_create_script_context(view.as_id(), 1, 0);
+    mock.fire_did_create_script_context(view.as_id(), 2, 0);
+}
+
+#[test]
+fn frames_form_a_tree() {
+    let mock = common::setup();
+    let view = WebView::default();
+    iframe_of_main(&mock, &view);
+
+    assert_eq!(view.frames().len(), 2);
+    let main = view.main_frame().unwrap();
+    assert!(main.is_main());
+    assert_eq!(main.parent(), None);
+
+    let iframe = main.children().pop().unwrap();
+    assert!(!iframe.is_main());
+    assert_eq!(iframe.parent(), Some(main));
+
+    iframe.insert_css("body { color: red; }");
+    let call = mock.calls_to("mbInsertCSSByFrame").pop().unwrap();
+    assert_eq!(call.args[1].as_ptr(), Some(2));
+}
+
+#[test]
+fn released_frame_is_stale() {
+    let mock = common::setup();
+    let view = WebView::default();
+    iframe_of_main(&mock, &view);
+    let iframe = view.main_frame().unwrap().children().pop().unwrap();
+
+    // Navigating the iframe releases its document.
+    mock.fire_will_release_script_context(view.as_id(), 2, 0);
+    assert!(!iframe.is_alive());
+    assert!(iframe.parent().is_none());
+    assert!(matches!(iframe.try_handle(), Err(MBError::StaleFrame)));
+    assert!(iframe.try_insert_css("body {}").is_err());
+    assert!(mock.calls_to("mbInsertCSSByFrame").is_empty());
+    assert!(view.main_frame().unwrap().children().is_empty());
+}
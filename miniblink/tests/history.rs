@@ -0,0 +1,93 @@
+mod common;
+
+use miniblink::backend::MockValue;
+use miniblink::history::MAX_ENTRIES;
+use miniblink::webview::WebView;
+
+#[test]
+fn visited_urls_are_recorded_with_titles() {
+    let mock = common::setup();
+    mock.respond("mbGetNavigateIndex", |_| Some(MockValue::Int(1)));
+    let view = WebView::default();
+
+    mock.fire_url_changed(view.as_id(), "https://example.com", false, false);
+    mock.fire_title_changed(view.as_id(), "Example");
+    mock.fire_url_changed(view.as_id(), "https://example.com/docs", true, false);
+
+    let history = view.history();
+    let titles: Vec<_> = history.entries().into_iter().map(|x| x.title).collect();
+    assert_eq!(titles, ["Example", ""]);
+    assert_eq!(history.current_index(), 1);
+
+    history.go_to_offset(-1);
+    assert_eq!(mock.calls_to("mbGoToOffset")[0].args[1].as_int(), Some(-1));
+}
+
+#[test]
+fn going_back_appends_the_url_again() {
+    let mock = common::setup();
+    let view = WebView::default();
+    mock.fire_url_changed(view.as_id(), "https://example.com", false, false);
+    mock.fire_url_changed(view.as_id(), "https://example.com/docs", true, false);
+    mock.fire_url_changed(view.as_id(), "https://example.com", true, true);
+
+    let urls: Vec<_> = view
+        .history()
+        .entries()
+        .into_iter()
+        .map(|x| x.url)
+        .collect();
+    assert_eq!(
+        urls,
+        [
+            "https://example.com",
+            "https://example.com/docs",
+            "https://example.com"
+        ]
+    );
+}
+
+#[test]
+fn visited_list_drops_the_oldest_entries() {
+    let mock = common::setup();
+    let view = WebView::default();
+    for i in 0..=MAX_ENTRIES {
+        mock.fire_url_changed(
+            view.as_id(),
+            &format!("https://example.com/{i}"),
+            true,
+            false,
+        );
+    }
+
+    let entries = view.history().entries();
+    assert_eq!(entries.len(), MAX_ENTRIES);
+    assert_eq!(entries[0].url, "https://example.com/1");
+}
+
+#[test]
+fn history_does_not_keep_the_view_alive() {
+    let mock = common::setup();
+    let view = WebView::default();
+    mock.fire_url_changed(view.as_id(), "https://example.com", false, false);
+    let history = view.history();
+
+    drop(view);
+    assert!(history.webview().is_none());
+    assert!(history.entries().is_empty());
+    assert!(history.try_current_index().is_err());
+}
+
+#[test]
+fn sync_checks_run_on_the_ui_thread() {
+    let mock = common::setup();
+    mock.respond("mbCanGoBackOrForward", |args| {
+        Some(MockValue::Int((args[1].as_int() == Some(1)) as i64))
+    });
+    let view = WebView::default();
+
+    let history = view.history();
+    assert!(history.can_go_back_sync());
+    assert!(!history.can_go_forward_sync());
+    assert_eq!(mock.calls_to("mbCallUiThreadSync").len(), 2);
+}
@@ -0,0 +1,28 @@
+mod common;
+
+use miniblink::backend::MockValue;
+use miniblink::types::JsValue;
+use miniblink::webview::WebView;
+
+#[test]
+fn eval_returns_a_number() {
+    let mock = common::setup();
+    mock.respond("mbGetJsValueType", |_| Some(MockValue::Int(0)));
+    mock.respond("mbJsToDouble", |_| Some(MockValue::Float(2.0)));
+
+    let view = WebView::default();
+    let value = view.eval(view.get_mainframe(), "return 1 + 1;", true);
+    assert!(matches!(value, JsValue::Number(x) if x == 2.0));
+    assert_eq!(i32::try_from(value).unwrap(), 2);
+}
+
+#[test]
+fn numbers_convert_within_bounds() {
+    // 2^63 does not fit an i64.
+    assert!(i64::try_from(JsValue::Number(9223372036854775808.0)).is_err());
+    assert!(i64::try_from(JsValue::Number(-9223372036854775808.0)).is_ok());
+    assert!(i32::try_from(JsValue::Number(2147483647.0)).is_ok());
+    assert!(i32::try_from(JsValue::Number(2147483648.0)).is_err());
+    assert!(i32::try_from(JsValue::Number(1.5)).is_err());
+    assert!(i32::try_from(JsValue::String("1".into())).is_err());
+}
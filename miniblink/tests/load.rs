@@ -0,0 +1,133 @@
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use miniblink::backend::MockValue;
+use miniblink::types::LoadFailureKind::{self, *};
+use miniblink::types::LoadState;
+use miniblink::webview::WebView;
+use miniblink_sys::{MB_LOADING_FAILED, MB_LOADING_SUCCEEDED};
+
+/// Answer every frame as the main frame, and the response head of every job with `head`.
+fn respond(mock: &common::Fixture, head: &[&str]) {
+    let head = MockValue::List(head.iter().map(|x| x.to_string()).collect());
+    mock.respond("mbIsMainFrame", |_| Some(MockValue::Int(1)));
+    mock.respond("mbNetGetRawResponseHeadInBlinkThread", move |_| {
+        Some(head.clone())
+    });
+}
+
+#[test]
+fn load_state_follows_the_main_frame() {
+    let mock = common::setup();
+    respond(&mock, &[]);
+    let view = WebView::default();
+    assert_eq!(view.load_state(), LoadState::Idle);
+
+    mock.fire_navigation(view.as_id(), 5, "https://example.com");
+    assert!(view.load_state().is_loading());
+
+    mock.fire_loading_finish(view.as_id(), 1, "https://example.com", MB_LOADING_FAILED);
+    assert!(matches!(view.load_state(), LoadState::Failed { .. }));
+
+    view.retry_load();
+    let load = mock.calls_to("mbLoadURL").pop().unwrap();
+    assert_eq!(load.args[1].as_str(), Some("https://example.com"));
+}
+
+#[test]
+fn error_page_is_rendered_for_failed_loads() {
+    let mock = common::setup();
+    respond(&mock, &["HTTP/1.1 404 Not Found", "Server: test"]);
+    let view = WebView::default();
+    view.set_error_page_handler(|_, failure| match (failure.kind, failure.status) {
+        (NameNotResolved, _) => Some("<h1>You are offline</h1>".into()),
+        (Http, Some(404)) => Some("<h1>Not found</h1>".into()),
+        _ => None,
+    });
+
+    let url = "https://example.com";
+    mock.fire_loading_finish(view.as_id(), 1, url, MB_LOADING_SUCCEEDED);
+    assert!(mock.calls_to("mbLoadHtmlWithBaseUrl").is_empty());
+
+    let reason = "net::ERR_NAME_NOT_RESOLVED";
+    mock.fire_loading_finish_with_reason(view.as_id(), 1, url, MB_LOADING_FAILED, reason);
+    let page = mock.calls_to("mbLoadHtmlWithBaseUrl").pop().unwrap();
+    assert_eq!(page.args[1].as_str(), Some("<h1>You are offline</h1>"));
+    assert_eq!(page.args[2].as_str(), Some(url));
+
+    // The error page finished, then a page which is not found loads.
+    mock.fire_loading_finish(view.as_id(), 1, url, MB_LOADING_SUCCEEDED);
+    let url = "https://example.com/missing";
+    mock.fire_navigation(view.as_id(), 5, url);
+    mock.fire_load_url_headers_received(view.as_id(), url, 2);
+    mock.fire_loading_finish(view.as_id(), 1, url, MB_LOADING_SUCCEEDED);
+
+    let page = mock.calls_to("mbLoadHtmlWithBaseUrl").pop().unwrap();
+    assert_eq!(page.args[1].as_str(), Some("<h1>Not found</h1>"));
+    assert_eq!(
+        view.load_state(),
+        LoadState::Failed {
+            url: url.into(),
+            reason: "HTTP/1.1 404 Not Found".into(),
+        }
+    );
+}
+
+#[test]
+fn head_without_status_line_succeeds() {
+    let mock = common::setup();
+    respond(&mock, &[]);
+    let view = WebView::default();
+
+    let url = "file:///index.html";
+    mock.fire_navigation(view.as_id(), 5, url);
+    mock.fire_load_url_headers_received(view.as_id(), url, 2);
+    mock.fire_loading_finish(view.as_id(), 1, url, MB_LOADING_SUCCEEDED);
+    assert_eq!(view.load_state(), LoadState::Loaded { url: url.into() });
+}
+
+#[test]
+fn failures_are_classified() {
+    let mock = common::setup();
+    respond(&mock, &["HTTP/1.1 503 Service Unavailable"]);
+    let view = WebView::default();
+    let kinds: Arc<Mutex<Vec<LoadFailureKind>>> = Arc::default();
+    let seen = kinds.clone();
+    view.set_error_page_handler(move |_, failure| {
+        seen.lock().unwrap().push(failure.kind);
+        None
+    });
+
+    let url = "https://example.com";
+    for reason in [
+        "net::ERR_NAME_NOT_RESOLVED",
+        "Couldn't resolve host name",
+        "net::ERR_CONNECTION_REFUSED",
+        "Couldn't connect to server",
+        "net::ERR_TIMED_OUT",
+        "Timeout was reached",
+        "net::ERR_BLOCKED_BY_CLIENT",
+        "net::ERR_CERT_INVALID",
+    ] {
+        mock.fire_loading_finish_with_reason(view.as_id(), 1, url, MB_LOADING_FAILED, reason);
+    }
+    mock.fire_navigation(view.as_id(), 5, url);
+    mock.fire_load_url_headers_received(view.as_id(), url, 1);
+    mock.fire_loading_finish(view.as_id(), 1, url, MB_LOADING_SUCCEEDED);
+
+    assert_eq!(
+        *kinds.lock().unwrap(),
+        [
+            NameNotResolved,
+            NameNotResolved,
+            ConnectionRefused,
+            ConnectionRefused,
+            TimedOut,
+            TimedOut,
+            Blocked,
+            Other,
+            Http,
+        ]
+    );
+}
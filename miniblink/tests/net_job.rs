@@ -0,0 +1,91 @@
+mod common;
+
+use std::sync::mpsc;
+
+use miniblink::net_job::HeldJob;
+use miniblink::webview::WebView;
+
+/// Hold every job of `view` and send it to the returned receiver.
+fn hold_jobs(view: &WebView) -> mpsc::Receiver<HeldJob> {
+    let (sender, receiver) = mpsc::channel();
+    view.on_load_url_begin(move |_, _, job| {
+        sender.send(job.hold()).unwrap();
+        true
+    });
+    receiver
+}
+
+#[test]
+fn held_job_is_answered_from_another_thread() {
+    let mock = common::setup();
+    let view = WebView::default();
+    let jobs = hold_jobs(&view);
+
+    assert_eq!(
+        mock.fire_load_url_begin(view.as_id(), "https://api.example/user", 7),
+        Some(true)
+    );
+    let job = jobs.recv().unwrap();
+    std::thread::spawn(move || {
+        job.respond(&[("Content-Type", "application/json")], r#"{"ok":true}"#);
+    })
+    .join()
+    .unwrap();
+
+    assert_eq!(
+        mock.calls_to("mbNetHoldJobToAsynCommit")[0].args[0].as_ptr(),
+        Some(7)
+    );
+    assert_eq!(
+        mock.calls_to("mbNetSetMIMEType")[0].args[1].as_str(),
+        Some("application/json")
+    );
+    assert_eq!(mock.calls_to("mbNetSetData")[0].args[2].as_int(), Some(11));
+    assert_eq!(
+        mock.calls_to("mbNetContinueJob")[0].args[0].as_ptr(),
+        Some(7)
+    );
+}
+
+#[test]
+fn body_stays_readable_after_respond() {
+    let mock = common::setup();
+    let view = WebView::default();
+    let jobs = hold_jobs(&view);
+
+    mock.fire_load_url_begin(view.as_id(), "https://api.example/body", 7);
+    jobs.recv().unwrap().respond(&[], "hello");
+
+    let call = mock.calls_to("mbNetSetData").pop().unwrap();
+    let data = call.args[1].as_ptr().unwrap() as *const u8;
+    let len = call.args[2].as_int().unwrap() as usize;
+    assert_eq!(unsafe { std::slice::from_raw_parts(data, len) }, b"hello");
+}
+
+#[test]
+fn dropped_job_resumes_the_request() {
+    let mock = common::setup();
+    let view = WebView::default();
+    let jobs = hold_jobs(&view);
+
+    mock.fire_load_url_begin(view.as_id(), "https://api.example/user", 7);
+    drop(jobs.recv().unwrap());
+    assert_eq!(mock.calls_to("mbNetContinueJob").len(), 1);
+}
+
+#[test]
+fn failed_commit_leaves_the_job_held() {
+    let mock = common::setup();
+    let view = WebView::default();
+    let jobs = hold_jobs(&view);
+
+    mock.fire_load_url_begin(view.as_id(), "https://api.example/user", 7);
+    mock.unsupported("mbCallUiThreadSync");
+    assert!(jobs
+        .recv()
+        .unwrap()
+        .try_redirect("https://example.com")
+        .is_err());
+    assert!(mock.calls_to("mbNetChangeRequestUrl").is_empty());
+    assert!(mock.calls_to("mbNetContinueJob").is_empty());
+}
@@ -0,0 +1,77 @@
+mod common;
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use miniblink::profile::Profile;
+use miniblink::types::CacheSettings;
+use miniblink::webview::WebView;
+
+#[test]
+fn profile_sets_the_paths_and_is_wiped_once_unused() {
+    let mock = common::setup();
+    let dir = common::TempDir::new("profile");
+    let root = dir.join("account");
+    let profile = Profile::new(&root).cache(CacheSettings::new().disk_limit(64 << 20));
+    let view = WebView::default();
+    view.set_profile(&profile);
+
+    let jar = mock.calls_to("mbSetCookieJarFullPath").pop().unwrap();
+    assert_eq!(jar.args[1].as_str(), root.join("cookies.dat").to_str());
+    assert_eq!(
+        mock.calls_to("mbSetDiskCacheLimitDisk")[0].args[1].as_int(),
+        Some(64 << 20)
+    );
+
+    assert!(profile.wipe().is_err());
+    assert!(Profile::new(root.join("Cache").join("..")).wipe().is_err());
+    view.destroy();
+    profile.wipe().unwrap();
+    assert!(!root.exists());
+}
+
+#[test]
+fn incognito_profile_is_removed_with_the_view() {
+    let mock = common::setup();
+    let view = WebView::default();
+    view.set_profile(&Profile::incognito());
+
+    let jar = mock.calls_to("mbSetCookieJarFullPath").pop().unwrap();
+    let dir = PathBuf::from(jar.args[1].as_str().unwrap())
+        .parent()
+        .unwrap()
+        .to_path_buf();
+    assert!(dir.exists());
+    assert_eq!(
+        mock.calls_to("mbSetDiskCacheEnabled")[0].args[1].as_int(),
+        Some(0)
+    );
+
+    view.destroy();
+    assert!(!dir.exists());
+}
+
+#[test]
+fn disk_cache_is_cleared_once_unused() {
+    let mock = common::setup();
+    let dir = common::TempDir::new("cache-settings");
+    let settings = CacheSettings::new()
+        .path(dir.join("cache"))
+        .disk_limit(256 << 20)
+        .level(1)
+        .resource_gc_interval(Duration::from_secs(30));
+    let view = WebView::default();
+    view.configure_cache(&settings);
+    assert_eq!(
+        mock.calls_to("mbSetResourceGc")[0].args[1].as_int(),
+        Some(30)
+    );
+    assert_eq!(
+        mock.calls_to("mbSetDiskCacheLevel")[0].args[1].as_int(),
+        Some(1)
+    );
+
+    assert!(settings.clear_disk_cache().is_err());
+    view.destroy();
+    settings.clear_disk_cache().unwrap();
+}
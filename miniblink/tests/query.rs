@@ -0,0 +1,107 @@
+mod common;
+
+use miniblink::backend::MockValue;
+use miniblink::events;
+use miniblink::params::{COMMAND_QUERY, QUERY_ERROR};
+use miniblink::webview::WebView;
+
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct Add {
+    a: i32,
+    b: i32,
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn typed_query_answers_and_reports_errors() {
+    let mock = common::setup();
+    let view = WebView::default();
+    view.handle_query(1, |_, req: Add| req.a.checked_add(req.b).ok_or("overflow"));
+
+    mock.fire_js_query(view.as_id(), 7, 1, r#"{"a": 1, "b": 2}"#);
+    let response = mock.calls_to("mbResponseQuery").pop().unwrap();
+    assert_eq!(response.args[2].as_int(), Some(1));
+    assert_eq!(response.args[3].as_str(), Some("3"));
+
+    mock.fire_js_query(view.as_id(), 8, 1, r#"{"a": 2147483647, "b": 1}"#);
+    let response = mock.calls_to("mbResponseQuery").pop().unwrap();
+    assert_eq!(response.args[2].as_int(), Some(QUERY_ERROR as i64));
+    assert_eq!(response.args[3].as_str(), Some(r#"{"message":"overflow"}"#));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn reserved_channel_is_rejected() {
+    let _mock = common::setup();
+    let view = WebView::default();
+    let reserved = view.try_handle_query::<Add, i64, _>(QUERY_ERROR, |_, _| Ok::<_, String>(0));
+    assert!(reserved.is_err());
+}
+
+#[test]
+fn routed_query_answers_on_its_channel() {
+    let mock = common::setup();
+    let view = WebView::default();
+    view.route_query(3, |_, req| Ok(req.to_uppercase()));
+
+    mock.fire_js_query(view.as_id(), 1, 3, "ping");
+    let response = mock.calls_to("mbResponseQuery").pop().unwrap();
+    assert_eq!(response.args[2].as_int(), Some(3));
+    assert_eq!(response.args[3].as_str(), Some("PING"));
+    assert!(view
+        .try_route_query(QUERY_ERROR, |_, _| Ok(String::new()))
+        .is_err());
+}
+
+#[test]
+fn command_prelude_and_invoke() {
+    let mock = common::setup();
+    let view = WebView::default();
+    view.command("greet", |_, args| match args {
+        "\"world\"" => Ok("\"hello, world\"".into()),
+        _ => Err("unknown name".into()),
+    });
+
+    // The prelude is injected before the page scripts run.
+    mock.fire_did_create_script_context(view.as_id(), 1, 0);
+    let prelude = mock.calls_to("mbRunJsSync").pop().unwrap();
+    assert!(prelude.args[2].as_str().unwrap().contains("rust.invoke"));
+
+    mock.fire_js_query(view.as_id(), 1, COMMAND_QUERY, "greet\n\"world\"");
+    let response = mock.calls_to("mbResponseQuery").pop().unwrap();
+    assert_eq!(response.args[3].as_str(), Some("\"hello, world\""));
+
+    mock.fire_js_query(view.as_id(), 2, COMMAND_QUERY, "greet\n\"moon\"");
+    let response = mock.calls_to("mbResponseQuery").pop().unwrap();
+    assert_eq!(response.args[2].as_int(), Some(QUERY_ERROR as i64));
+
+    assert!(view.try_command("", |_, _| Ok(String::new())).is_err());
+    assert!(view.try_command("a\nb", |_, _| Ok(String::new())).is_err());
+}
+
+#[test]
+fn emit_escapes_the_payload() {
+    let mock = common::setup();
+    let view = WebView::default();
+
+    view.emit("progress", "50% \"done\"");
+    let call = mock.calls_to("mbRunJsSync").pop().unwrap();
+    assert_eq!(
+        call.args[2].as_str(),
+        Some(r#"window.dispatchEvent(new CustomEvent("progress", { detail: "50% \"done\"" }));"#)
+    );
+}
+
+#[test]
+fn emit_after_vetoed_navigation_targets_the_current_document() {
+    let mock = common::setup();
+    let view = WebView::default();
+    view.emit("progress", "50%");
+
+    view.subscribe::<events::Navigation, _>(0, |_, _| false);
+    mock.respond("mbIsLoading", |_| Some(MockValue::Int(1)));
+    mock.fire_navigation(view.as_id(), 0, "https://example.com/");
+    view.emit("progress", "100%");
+    assert_eq!(mock.calls_to("mbRunJsSync").len(), 2);
+}
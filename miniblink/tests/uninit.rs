@@ -0,0 +1,32 @@
+//! `app::uninit` is terminal for the process, so it runs in its own test binary.
+mod common;
+
+use miniblink::error::MBError;
+use miniblink::types::JsValue;
+use miniblink::{app, webview::WebView};
+
+#[test]
+fn uninit_destroys_views_and_disables_the_api() {
+    let mock = common::setup();
+    let view = WebView::default();
+    let other = WebView::default();
+
+    app::uninit();
+    assert!(view.is_destroyed());
+    assert!(other.is_destroyed());
+    assert_eq!(mock.calls_to("mbDestroyWebView").len(), 2);
+    assert_eq!(mock.calls_to("mbUninit").len(), 1);
+
+    assert!(matches!(
+        app::try_exit_message_loop(),
+        Err(MBError::NotInitialized)
+    ));
+    assert!(matches!(app::try_uninit(), Err(MBError::NotInitialized)));
+    app::exit_message_loop();
+    app::uninit();
+    assert_eq!(view.url(), "");
+    assert!(matches!(
+        view.eval(view.get_mainframe(), "1", false),
+        JsValue::Undefined
+    ));
+}
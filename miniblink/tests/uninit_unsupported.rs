@@ -0,0 +1,16 @@
+//! `app::uninit` is terminal for the process, so it runs in its own test binary.
+mod common;
+
+use miniblink::{app, webview::WebView};
+
+#[test]
+fn uninit_without_export_still_destroys_views() {
+    let mock = common::setup();
+    let view = WebView::default();
+    mock.unsupported("mbUninit");
+
+    app::uninit();
+    assert!(view.is_destroyed());
+    assert_eq!(mock.calls_to("mbDestroyWebView").len(), 1);
+    assert!(app::try_uninit().is_err());
+}
@@ -0,0 +1,200 @@
+mod common;
+
+use std::pin::pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::Poll;
+
+use miniblink::backend::MockValue;
+use miniblink::error::MBError;
+use miniblink::types::{ConsoleLevel, CursorType, JsValue, LoadingResult, Size};
+use miniblink::{events, webview::WebView};
+
+use common::poll_once;
+
+#[test]
+fn destroyed_view_stops_calling_miniblink() {
+    let mock = common::setup();
+    let view = WebView::default();
+
+    view.destroy();
+    assert!(matches!(view.try_reload(), Err(MBError::Destroyed)));
+    view.reload();
+    assert!(mock.calls_to("mbReload").is_empty());
+}
+
+#[test]
+fn destroyed_view_returns_defaults() {
+    let mock = common::setup();
+    let view = WebView::default();
+    view.destroy();
+    let calls = mock.calls().len();
+
+    assert!(matches!(
+        view.eval(view.get_mainframe(), "1", false),
+        JsValue::Undefined
+    ));
+    assert_eq!(view.cursor_type(), CursorType::Pointer);
+    let _ = view.get_window_handle();
+    view.subscribe::<events::Navigation, _>(0, |_, _| true)
+        .unsubscribe();
+    assert_eq!(mock.calls().len(), calls);
+}
+
+#[test]
+fn source_sync_decodes_utf8() {
+    let mock = common::setup();
+    mock.respond("mbGetSourceSync", |_| Some("<p>héllo</p>".into()));
+
+    let view = WebView::default();
+    assert_eq!(view.source_sync(), "<p>héllo</p>");
+}
+
+#[test]
+fn markup_completes_from_the_callback() {
+    let mock = common::setup();
+    let view = WebView::default();
+    let mut markup = pin!(view.markup(view.get_mainframe()));
+    assert!(poll_once(markup.as_mut()).is_pending());
+
+    mock.fire_get_content_as_markup(view.as_id(), "<p>héllo</p>");
+    assert!(matches!(
+        poll_once(markup.as_mut()),
+        Poll::Ready(Ok(markup)) if markup == "<p>héllo</p>"
+    ));
+}
+
+#[test]
+fn save_as_mhtml_writes_the_file() {
+    let mock = common::setup();
+    let dir = common::TempDir::new("save-as-mhtml");
+    let path = dir.join("page.mhtml");
+
+    let view = WebView::default();
+    let mut save = pin!(view.save_as_mhtml(&path));
+    mock.fire_get_source(view.as_id(), "mbUtilSerializeToMHTML", "MIME-Version: 1.0");
+    assert!(matches!(poll_once(save.as_mut()), Poll::Ready(Ok(()))));
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "MIME-Version: 1.0");
+}
+
+#[test]
+fn content_size_reads_both_dimensions() {
+    let mock = common::setup();
+    mock.respond("mbGetContentWidth", |_| Some(MockValue::Int(800)));
+    mock.respond("mbGetContentHeight", |_| Some(MockValue::Int(2400)));
+
+    let view = WebView::default();
+    assert_eq!(
+        view.content_size(),
+        Size {
+            width: 800,
+            height: 2400
+        }
+    );
+}
+
+#[test]
+fn replaced_callback_is_unregistered() {
+    let mock = common::setup();
+    let view = WebView::default();
+    let calls = Arc::new(AtomicUsize::new(0));
+    let first = view.on_title_changed(|_, _| {});
+    let counter = calls.clone();
+    let second = view.on_title_changed(move |_, _| {
+        counter.fetch_add(1, Ordering::SeqCst);
+    });
+    assert!(!first.is_registered());
+
+    let guard = second.into_guard();
+    mock.fire_title_changed(view.as_id(), "hello");
+    drop(guard);
+    mock.fire_title_changed(view.as_id(), "hello");
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn revoked_on_handler_keeps_other_listeners() {
+    let mock = common::setup();
+    let view = WebView::default();
+    let handle = view.on_url_changed(|_, _| {});
+    mock.fire_url_changed(view.as_id(), "https://miniblink.net/", false, false);
+    handle.revoke();
+
+    // The history is fed by a listener of the same event.
+    mock.fire_url_changed(view.as_id(), "https://miniblink.net/docs", true, false);
+    assert_eq!(view.history().entries().len(), 2);
+}
+
+#[test]
+fn init_script_runs_in_the_main_world_only() {
+    let mock = common::setup();
+    let view = WebView::default();
+    view.add_init_script("window.embedded = true;");
+
+    mock.fire_did_create_script_context(view.as_id(), 1, 0);
+    let call = mock.calls_to("mbRunJsSync").pop().unwrap();
+    assert_eq!(call.args[1].as_ptr(), Some(1));
+    assert_eq!(call.args[2].as_str(), Some("window.embedded = true;"));
+
+    mock.clear_calls();
+    mock.fire_did_create_script_context(view.as_id(), 2, 1);
+    assert!(mock.calls_to("mbRunJsSync").is_empty());
+}
+
+#[test]
+fn console_message_is_passed_to_the_callback() {
+    let mock = common::setup();
+    let view = WebView::default();
+    let seen = Arc::new(AtomicUsize::new(0));
+    let counter = seen.clone();
+    view.on_console(move |_, msg| {
+        assert_eq!(msg.level, ConsoleLevel::Error);
+        assert_eq!(msg.message, "Uncaught TypeError: x is undefined");
+        assert_eq!(
+            (msg.source.as_str(), msg.line),
+            ("https://example.com/app.js", 12)
+        );
+        counter.fetch_add(1, Ordering::SeqCst);
+    });
+
+    mock.fire_console(
+        view.as_id(),
+        miniblink_sys::mbLevelError,
+        "Uncaught TypeError: x is undefined",
+        "https://example.com/app.js",
+        12,
+    );
+    assert_eq!(seen.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn wait_for_load_completes_on_the_main_frame() {
+    let mock = common::setup();
+    mock.respond("mbIsMainFrame", |args| {
+        Some(MockValue::Int((args[1].as_ptr() == Some(1)) as i64))
+    });
+    let view = WebView::default();
+    let mut load = pin!(view.wait_for_load());
+    assert!(poll_once(load.as_mut()).is_pending());
+
+    mock.fire_loading_finish(view.as_id(), 2, "https://miniblink.net/frame", 0);
+    assert!(poll_once(load.as_mut()).is_pending());
+    mock.fire_loading_finish(view.as_id(), 1, "https://miniblink.net/", 0);
+    assert!(matches!(
+        poll_once(load.as_mut()),
+        Poll::Ready(Ok(LoadingResult::Succeeded))
+    ));
+}
+
+#[test]
+fn dropped_wait_for_load_removes_its_listener() {
+    let mock = common::setup();
+    mock.respond("mbIsMainFrame", |_| Some(MockValue::Int(1)));
+    let view = WebView::default();
+
+    drop(view.wait_for_load());
+    mock.clear_calls();
+    mock.fire_loading_finish(view.as_id(), 1, "https://miniblink.net/", 0);
+    // Only the load tracker is left.
+    assert_eq!(mock.calls_to("mbIsMainFrame").len(), 1);
+}